@@ -0,0 +1,76 @@
+use core::arch::wasm32::{u8x16_eq, u8x16_splat, v128, v128_andnot, v128_bitselect, v128_or};
+
+use crate::impl_v128::{u8x16_load, u8x16_to_array};
+
+/// [`Alphabet`] selects the 64 symbols used for encoding and decoding.
+///
+/// The SIMD kernels work in the standard alphabet, other alphabets are mapped onto it a
+/// chunk at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    /// RFC 4648 §4: `A-Z`, `a-z`, `0-9`, `+` and `/`.
+    Standard,
+    /// RFC 4648 §5: `A-Z`, `a-z`, `0-9`, `-` and `_`.
+    UrlSafe,
+}
+
+/// The standard alphabet.
+pub const STANDARD: Alphabet = Alphabet::Standard;
+
+/// The URL and filename safe alphabet.
+pub const URL_SAFE: Alphabet = Alphabet::UrlSafe;
+
+impl Alphabet {
+    /// [`Alphabet::remap_encoded`] rewrites an encoded chunk from the standard alphabet
+    /// into this one.
+    #[inline]
+    pub(crate) fn remap_encoded(self, encoded: v128) -> v128 {
+        match self {
+            Self::Standard => encoded,
+            Self::UrlSafe => {
+                let plus = u8x16_eq(encoded, u8x16_splat(b'+'));
+                let slash = u8x16_eq(encoded, u8x16_splat(b'/'));
+
+                let encoded = v128_bitselect(u8x16_splat(b'-'), encoded, plus);
+                v128_bitselect(u8x16_splat(b'_'), encoded, slash)
+            }
+        }
+    }
+
+    /// [`Alphabet::remap_ascii`] rewrites an ascii chunk from this alphabet into the
+    /// standard one. Symbols that only exist in the standard alphabet become `0`, so
+    /// `decode_chunk` rejects them.
+    #[inline]
+    pub(crate) fn remap_ascii(self, ascii: &[u8; 16]) -> [u8; 16] {
+        match self {
+            Self::Standard => *ascii,
+            Self::UrlSafe => {
+                let ascii = u8x16_load(ascii);
+                let standard_only = v128_or(
+                    u8x16_eq(ascii, u8x16_splat(b'+')),
+                    u8x16_eq(ascii, u8x16_splat(b'/')),
+                );
+                let dash = u8x16_eq(ascii, u8x16_splat(b'-'));
+                let underscore = u8x16_eq(ascii, u8x16_splat(b'_'));
+
+                let ascii = v128_andnot(ascii, standard_only);
+                let ascii = v128_bitselect(u8x16_splat(b'+'), ascii, dash);
+                let ascii = v128_bitselect(u8x16_splat(b'/'), ascii, underscore);
+
+                u8x16_to_array(ascii)
+            }
+        }
+    }
+
+    /// [`Alphabet::sextet`] returns the 6-bit value of a single symbol.
+    pub(crate) const fn sextet(self, symbol: u8) -> Option<u8> {
+        match (self, symbol) {
+            (_, b'A'..=b'Z') => Some(symbol - b'A'),
+            (_, b'a'..=b'z') => Some(symbol - b'a' + 26),
+            (_, b'0'..=b'9') => Some(symbol - b'0' + 52),
+            (Self::Standard, b'+') | (Self::UrlSafe, b'-') => Some(62),
+            (Self::Standard, b'/') | (Self::UrlSafe, b'_') => Some(63),
+            _ => None,
+        }
+    }
+}
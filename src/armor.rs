@@ -0,0 +1,415 @@
+//! OpenPGP ASCII armor ([RFC 4880 §6]): `-----BEGIN PGP ...-----`, `Key: Value` armor
+//! headers, a base64 body and the `=XXXX` CRC-24 checksum line.
+//!
+//! The checksum is folded in as the decode loop writes each chunk, so the body is only read
+//! once. Cleartext signed messages are a different framework and not handled here.
+//!
+//! [RFC 4880 §6]: https://www.rfc-editor.org/rfc/rfc4880#section-6
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use js_sys::{Object, Reflect, Uint8Array};
+#[cfg(feature = "std")]
+use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "std")]
+use wasm_bindgen::JsValue;
+
+use crate::alphabet::Alphabet;
+#[cfg(feature = "std")]
+use crate::buffer_source::byte_view;
+use crate::len::{self, LineEnding};
+use crate::{
+    decode_into, decode_symbols_with, encode_into, encode_wrapped_to, text_offset, DecodeError,
+};
+#[cfg(feature = "std")]
+use crate::{entries_object, string_entries};
+
+/// Symbols per body line. RFC 4880 allows up to 76, 64 is what implementations emit.
+pub const LINE_LEN: usize = 64;
+
+const CRC24_INIT: u32 = 0xb7_04ce;
+const CRC24_POLY: u32 = 0x186_4cfb;
+
+const CRC24_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = (i as u32) << 16;
+        let mut bit = 0;
+        while bit < 8 {
+            crc <<= 1;
+            if crc & 0x100_0000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+            bit += 1;
+        }
+
+        table[i] = crc & 0xff_ffff;
+        i += 1;
+    }
+
+    table
+};
+
+/// [`Armor`] is one armored block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Armor {
+    /// What follows `BEGIN`, such as `PGP MESSAGE` or `PGP SIGNATURE`.
+    pub label: String,
+    /// The armor headers in order.
+    pub headers: Vec<(String, String)>,
+    pub bytes: Vec<u8>,
+}
+
+/// [`ArmorError`] describes why armored text could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmorError {
+    /// There is no `-----BEGIN PGP ...-----` line.
+    MissingBegin,
+    /// The block has no `-----END` line.
+    MissingEnd,
+    /// The `-----END` label differs from the `-----BEGIN` label.
+    LabelMismatch,
+    /// An armor header is not `Key: Value`.
+    InvalidHeader,
+    /// The checksum line is not `=` and four symbols, or is not the last line of the body.
+    InvalidChecksum,
+    /// The checksum line (first) disagrees with the CRC-24 of the decoded body (second).
+    ChecksumMismatch(u32, u32),
+    /// The base64 body is invalid. Offsets index into the text passed to [`decode`].
+    InvalidBase64(DecodeError),
+}
+
+impl fmt::Display for ArmorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingBegin => write!(f, "missing BEGIN PGP line"),
+            Self::MissingEnd => write!(f, "missing END PGP line"),
+            Self::LabelMismatch => write!(f, "END label does not match BEGIN label"),
+            Self::InvalidHeader => write!(f, "invalid armor header"),
+            Self::InvalidChecksum => write!(f, "invalid checksum line"),
+            Self::ChecksumMismatch(expected, actual) => {
+                write!(f, "checksum {expected:06x} does not match {actual:06x}")
+            }
+            Self::InvalidBase64(err) => write!(f, "invalid base64 body: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ArmorError {}
+
+#[cfg(feature = "std")]
+impl From<ArmorError> for JsValue {
+    fn from(err: ArmorError) -> Self {
+        Self::from_str(&err.to_string())
+    }
+}
+
+/// [`decode_armor_js`] decodes the first armored block in `text` into
+/// `{ label, headers, bytes }`.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = decode_armor)]
+pub fn decode_armor_js(text: &str) -> Result<Object, JsValue> {
+    let Armor {
+        label,
+        headers,
+        bytes,
+    } = decode(text)?;
+
+    let headers = entries_object(&headers)?;
+
+    let armor = Object::new();
+    Reflect::set(&armor, &"label".into(), &label.into())?;
+    Reflect::set(&armor, &"headers".into(), &headers)?;
+    Reflect::set(&armor, &"bytes".into(), &Uint8Array::from(bytes.as_slice()))?;
+
+    Ok(armor)
+}
+
+/// [`encode_armor_js`] armors bytes under `label`, taking armor headers from the entries of
+/// `headers`.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = encode_armor)]
+pub fn encode_armor_js(
+    label: &str,
+    bytes: &JsValue,
+    headers: Option<Object>,
+) -> Result<String, JsValue> {
+    let bytes = byte_view(bytes)?.to_vec();
+
+    let headers = string_entries(headers);
+    let headers: Vec<(&str, &str)> = headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+
+    Ok(encode(label, &headers, &bytes, LineEnding::Lf))
+}
+
+/// [`crc24`] returns the OpenPGP CRC-24 of `bytes`.
+pub fn crc24(bytes: &[u8]) -> u32 {
+    crc24_update(CRC24_INIT, bytes)
+}
+
+fn crc24_update(crc: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(crc, |crc, &byte| {
+        let index = ((crc >> 16) as u8 ^ byte) as usize;
+        ((crc << 8) ^ CRC24_TABLE[index]) & 0xff_ffff
+    })
+}
+
+/// [`encode`] armors `bytes` under `label`, for example `PGP SIGNATURE`, with the body
+/// wrapped at [`LINE_LEN`] columns and a checksum line.
+pub fn encode(
+    label: &str,
+    headers: &[(&str, &str)],
+    bytes: &[u8],
+    line_ending: LineEnding,
+) -> String {
+    let eol = line_ending.as_str();
+
+    let mut armor = String::new();
+    armor.push_str("-----BEGIN ");
+    armor.push_str(label);
+    armor.push_str("-----");
+    armor.push_str(eol);
+
+    for (name, value) in headers {
+        armor.push_str(name);
+        armor.push_str(": ");
+        armor.push_str(value);
+        armor.push_str(eol);
+    }
+    armor.push_str(eol);
+
+    if !bytes.is_empty() {
+        let mut body = Vec::new();
+        encode_wrapped_to(bytes, LINE_LEN, line_ending, &mut body)
+            .expect("LINE_LEN is nonzero and a slice's wrapped encoding fits in usize");
+
+        // SAFETY: base64 and line endings are ascii.
+        armor.push_str(unsafe { core::str::from_utf8_unchecked(&body) });
+        armor.push_str(eol);
+    }
+
+    let mut checksum = [0u8; 4];
+    let crc = crc24(bytes).to_be_bytes();
+    encode_into(&crc[1..], &mut checksum).expect("3 bytes encode to 4 symbols");
+
+    armor.push('=');
+    // SAFETY: base64 is ascii.
+    armor.push_str(unsafe { core::str::from_utf8_unchecked(&checksum) });
+    armor.push_str(eol);
+
+    armor.push_str("-----END ");
+    armor.push_str(label);
+    armor.push_str("-----");
+    armor.push_str(eol);
+    armor
+}
+
+/// [`decode`] decodes the first armored block in `text`, ignoring anything around it. The
+/// checksum line is optional, but checked when present.
+pub fn decode(text: &str) -> Result<Armor, ArmorError> {
+    let mut lines = text.split('\n').map(str::trim_end);
+
+    let label = lines
+        .by_ref()
+        .find_map(|line| boundary(line, "BEGIN"))
+        .ok_or(ArmorError::MissingBegin)?;
+
+    let mut headers = Vec::new();
+    loop {
+        let line = lines.next().ok_or(ArmorError::MissingEnd)?;
+        if line.is_empty() {
+            break;
+        }
+
+        let (name, value) = line.split_once(": ").ok_or(ArmorError::InvalidHeader)?;
+        headers.push((name.to_string(), value.to_string()));
+    }
+
+    let mut body = Vec::new();
+    let mut checksum = None;
+    loop {
+        let line = lines.next().ok_or(ArmorError::MissingEnd)?;
+
+        if let Some(end) = boundary(line, "END") {
+            if end != label {
+                return Err(ArmorError::LabelMismatch);
+            }
+            break;
+        }
+
+        if checksum.is_some() {
+            return Err(ArmorError::InvalidChecksum);
+        }
+
+        match line.strip_prefix('=') {
+            Some(crc) => checksum = Some(parse_checksum(crc)?),
+            None => body.push(line),
+        }
+    }
+
+    let invalid = |err: DecodeError| {
+        let lines = body.iter().copied();
+        ArmorError::InvalidBase64(err.map_offset(|index| text_offset(text, lines, 0, index)))
+    };
+
+    let symbols = body.concat().into_bytes();
+    let mut bytes = vec![0u8; len::decoded_len(&symbols).map_err(invalid)?];
+    let symbols = match symbols.as_slice() {
+        [p @ .., b'=', b'='] | [p @ .., b'='] | p => p,
+    };
+
+    let mut crc = CRC24_INIT;
+    decode_symbols_with(symbols, &mut bytes, Alphabet::Standard, |decoded| {
+        crc = crc24_update(crc, decoded);
+    })
+    .map_err(invalid)?;
+
+    match checksum {
+        Some(expected) if expected != crc => Err(ArmorError::ChecksumMismatch(expected, crc)),
+        _ => Ok(Armor {
+            label: label.to_string(),
+            headers,
+            bytes,
+        }),
+    }
+}
+
+/// [`boundary`] returns the label of a `-----<kind> PGP ...-----` line.
+fn boundary<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    let label = line
+        .strip_prefix("-----")?
+        .strip_prefix(kind)?
+        .strip_prefix(' ')?
+        .strip_suffix("-----")?;
+
+    label.starts_with("PGP ").then_some(label)
+}
+
+fn parse_checksum(symbols: &str) -> Result<u32, ArmorError> {
+    let mut crc = [0u8; 4];
+
+    match decode_into(symbols.as_bytes(), &mut crc[1..]) {
+        Ok(3) if symbols.len() == 4 => Ok(u32::from_be_bytes(crc)),
+        _ => Err(ArmorError::InvalidChecksum),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_crc24() {
+        assert_eq!(crc24(b""), CRC24_INIT);
+        assert_eq!(crc24(b"123456789"), 0x21_cf02);
+
+        let (head, tail) = b"123456789".split_at(4);
+        assert_eq!(crc24_update(crc24(head), tail), 0x21_cf02);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_encode() {
+        let armor = encode(
+            "PGP MESSAGE",
+            &[("Comment", "hi")],
+            b"hello world",
+            LineEnding::Lf,
+        );
+
+        assert_eq!(
+            armor,
+            "-----BEGIN PGP MESSAGE-----\nComment: hi\n\naGVsbG8gd29ybGQ=\n=sDy3\n-----END PGP MESSAGE-----\n"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_round_trip() -> Result<(), ArmorError> {
+        let bytes: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+
+        for line_ending in [LineEnding::Lf, LineEnding::CrLf] {
+            for len in [0, 1, 2, 12, 47, 48, 1000] {
+                let armor = encode("PGP SIGNATURE", &[], &bytes[..len], line_ending);
+                let text = ["explanatory text", line_ending.as_str(), &armor].concat();
+
+                assert_eq!(
+                    decode(&text)?,
+                    Armor {
+                        label: "PGP SIGNATURE".to_string(),
+                        headers: Vec::new(),
+                        bytes: bytes[..len].to_vec(),
+                    }
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_checksum() -> Result<(), ArmorError> {
+        let armor =
+            "-----BEGIN PGP MESSAGE-----\n\naGVsbG8gd29ybGQ=\n=sDy3\n-----END PGP MESSAGE-----";
+        assert_eq!(decode(armor)?.bytes, b"hello world");
+
+        let tampered = armor.replace("aGVsbG8", "aGVsbG9");
+        assert!(matches!(
+            decode(&tampered),
+            Err(ArmorError::ChecksumMismatch(0xb0_3cb7, _))
+        ));
+
+        let missing = armor.replace("=sDy3\n", "");
+        assert_eq!(decode(&missing)?.bytes, b"hello world");
+
+        let malformed = armor.replace("=sDy3", "=sDy");
+        assert_eq!(decode(&malformed), Err(ArmorError::InvalidChecksum));
+
+        let misplaced = armor.replace("=sDy3\n", "=sDy3\naGVs\n");
+        assert_eq!(decode(&misplaced), Err(ArmorError::InvalidChecksum));
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_errors() {
+        assert_eq!(
+            decode("-----BEGIN CERTIFICATE-----\n"),
+            Err(ArmorError::MissingBegin)
+        );
+        assert_eq!(
+            decode("-----BEGIN PGP MESSAGE-----\n\nZm9v\n"),
+            Err(ArmorError::MissingEnd)
+        );
+        assert_eq!(
+            decode("-----BEGIN PGP MESSAGE-----\n\n-----END PGP SIGNATURE-----"),
+            Err(ArmorError::LabelMismatch)
+        );
+        assert_eq!(
+            decode("-----BEGIN PGP MESSAGE-----\nComment\n\n-----END PGP MESSAGE-----"),
+            Err(ArmorError::InvalidHeader)
+        );
+        assert_eq!(
+            decode("-----BEGIN PGP MESSAGE-----\n\nZm!v\n-----END PGP MESSAGE-----"),
+            Err(ArmorError::InvalidBase64(DecodeError::InvalidByte(
+                31, b'!'
+            )))
+        );
+        assert_eq!(
+            decode(
+                "x\r\n-----BEGIN PGP MESSAGE-----\r\n\r\nZm9v\r\nYm!y\r\n-----END PGP MESSAGE-----"
+            ),
+            Err(ArmorError::InvalidBase64(DecodeError::InvalidByte(
+                42, b'!'
+            )))
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Encode or decode many small buffers in one call, so crossing the JS boundary is paid once
+//! per batch rather than once per item.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use js_sys::Array;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+
+#[cfg(feature = "std")]
+use crate::buffer_source::byte_view;
+use crate::{decode_to, encode_to, DecodeError};
+
+/// [`Batch`] packs every output of a batch into one buffer. Item `i` is
+/// `data[offsets[i]..offsets[i + 1]]`, and an item that failed is empty and listed in
+/// `errors`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct Batch {
+    data: Vec<u8>,
+    offsets: Vec<u32>,
+    errors: Vec<(u32, DecodeError)>,
+}
+
+impl Batch {
+    fn with_capacity(items: usize) -> Self {
+        let mut offsets = Vec::with_capacity(items + 1);
+        offsets.push(0);
+
+        Self {
+            data: Vec::new(),
+            offsets,
+            errors: Vec::new(),
+        }
+    }
+
+    fn finish_item(&mut self) {
+        let offset = u32::try_from(self.data.len()).expect("Batch output exceeds u32::MAX bytes");
+        self.offsets.push(offset);
+    }
+
+    /// [`Batch::len`] returns the number of items.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// [`Batch::get`] returns the output of item `index`, or why it failed.
+    pub fn get(&self, index: usize) -> Option<Result<&[u8], DecodeError>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        match self.errors.iter().find(|(i, _)| *i as usize == index) {
+            Some(&(_, err)) => Some(Err(err)),
+            None => {
+                let start = self.offsets[index] as usize;
+                let end = self.offsets[index + 1] as usize;
+                Some(Ok(&self.data[start..end]))
+            }
+        }
+    }
+
+    /// [`Batch::data`] returns the packed outputs.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// [`Batch::offsets`] returns the `len() + 1` item boundaries in [`Batch::data`].
+    pub fn offsets(&self) -> &[u32] {
+        &self.offsets
+    }
+
+    /// [`Batch::errors`] returns the failed items, by index.
+    pub fn errors(&self) -> &[(u32, DecodeError)] {
+        &self.errors
+    }
+}
+
+#[wasm_bindgen]
+impl Batch {
+    #[wasm_bindgen(getter = data)]
+    pub fn data_js(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+
+    #[wasm_bindgen(getter = offsets)]
+    pub fn offsets_js(&self) -> Vec<u32> {
+        self.offsets.clone()
+    }
+
+    /// The indices of the items that failed.
+    #[wasm_bindgen(getter = failed)]
+    pub fn failed_js(&self) -> Vec<u32> {
+        self.errors.iter().map(|&(index, _)| index).collect()
+    }
+
+    /// Why item `index` failed, if it did.
+    #[wasm_bindgen(js_name = error)]
+    pub fn error_js(&self, index: u32) -> Option<String> {
+        self.errors
+            .iter()
+            .find(|&&(i, _)| i == index)
+            .map(|(_, err)| alloc::string::ToString::to_string(err))
+    }
+}
+
+/// [`encode_batch`] encodes every item with padding into one [`Batch`].
+///
+/// # Panics
+///
+/// Panics if the packed output exceeds `u32::MAX` bytes.
+pub fn encode_batch<'a>(items: impl IntoIterator<Item = &'a [u8]>) -> Batch {
+    let items = items.into_iter();
+    let mut batch = Batch::with_capacity(items.size_hint().0);
+
+    for item in items {
+        if !item.is_empty() {
+            encode_to(item, &mut batch.data).expect("Item is not empty");
+        }
+        batch.finish_item();
+    }
+
+    batch
+}
+
+/// [`decode_batch`] decodes every item into one [`Batch`]. An item that fails is recorded
+/// and left empty, the rest of the batch carries on.
+///
+/// # Panics
+///
+/// Panics if the packed output exceeds `u32::MAX` bytes or the batch `u32::MAX` items.
+pub fn decode_batch<'a>(items: impl IntoIterator<Item = &'a [u8]>) -> Batch {
+    let items = items.into_iter();
+    let mut batch = Batch::with_capacity(items.size_hint().0);
+
+    for (index, item) in items.enumerate() {
+        if let Err(err) = decode_to(item, &mut batch.data) {
+            let index = u32::try_from(index).expect("Batch exceeds u32::MAX items");
+            batch.errors.push((index, err));
+        }
+        batch.finish_item();
+    }
+
+    batch
+}
+
+/// [`encode_batch_js`] encodes the items packed in `data`, item `i` being
+/// `data[offsets[i]..offsets[i + 1]]`.
+#[cfg(not(feature = "std"))]
+#[wasm_bindgen(js_name = encode_batch)]
+pub fn encode_batch_js(data: &[u8], offsets: &[u32]) -> Result<Batch, JsValue> {
+    Ok(encode_batch(unpack(data, offsets).ok_or(UNPACK_ERROR)?))
+}
+
+/// [`encode_batch_js`] encodes the items packed in any `BufferSource`, item `i` being its
+/// bytes `offsets[i]..offsets[i + 1]`.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = encode_batch)]
+pub fn encode_batch_js(data: &JsValue, offsets: &[u32]) -> Result<Batch, JsValue> {
+    let data = byte_view(data)?.to_vec();
+    let items = unpack(&data, offsets).ok_or(UNPACK_ERROR)?;
+    Ok(encode_batch(items))
+}
+
+/// [`decode_batch_js`] decodes the items packed in `ascii`, item `i` being
+/// `ascii[offsets[i]..offsets[i + 1]]`.
+#[wasm_bindgen(js_name = decode_batch)]
+pub fn decode_batch_js(ascii: &[u8], offsets: &[u32]) -> Result<Batch, JsValue> {
+    Ok(decode_batch(unpack(ascii, offsets).ok_or(UNPACK_ERROR)?))
+}
+
+/// [`encode_batch_array_js`] encodes an array of `BufferSource`s or strings.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = encode_batch_array)]
+pub fn encode_batch_array_js(items: Array) -> Result<Batch, JsValue> {
+    let items = items_to_vecs(&items)?;
+    Ok(encode_batch(items.iter().map(Vec::as_slice)))
+}
+
+/// [`decode_batch_array_js`] decodes an array of `BufferSource`s or strings.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = decode_batch_array)]
+pub fn decode_batch_array_js(items: Array) -> Result<Batch, JsValue> {
+    let items = items_to_vecs(&items)?;
+    Ok(decode_batch(items.iter().map(Vec::as_slice)))
+}
+
+const UNPACK_ERROR: &str = "offsets must start at 0, never decrease and end at the data length";
+
+/// [`unpack`] splits a packed buffer at `offsets`, which must start at `0`, never decrease
+/// and end at `data.len()`.
+fn unpack<'a>(data: &'a [u8], offsets: &'a [u32]) -> Option<impl Iterator<Item = &'a [u8]>> {
+    let in_order = offsets.windows(2).all(|pair| pair[0] <= pair[1]);
+
+    match (offsets.first(), offsets.last()) {
+        (Some(0), Some(&end)) if in_order && end as usize == data.len() => Some(
+            offsets
+                .windows(2)
+                .map(|pair| &data[pair[0] as usize..pair[1] as usize]),
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "std")]
+fn items_to_vecs(items: &Array) -> Result<Vec<Vec<u8>>, JsValue> {
+    items
+        .iter()
+        .map(|item| match item.as_string() {
+            Some(item) => Ok(item.into_bytes()),
+            None => Ok(byte_view(&item)?.to_vec()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_encode_batch() {
+        let batch = encode_batch([&b"Hello"[..], b"", b"World", b"foobar"]);
+
+        assert_eq!(batch.len(), 4);
+        assert_eq!(batch.data(), b"SGVsbG8=V29ybGQ=Zm9vYmFy");
+        assert_eq!(batch.offsets(), &[0, 8, 8, 16, 24]);
+        assert_eq!(batch.get(1), Some(Ok(&b""[..])));
+        assert_eq!(batch.get(3), Some(Ok(&b"Zm9vYmFy"[..])));
+        assert_eq!(batch.get(4), None);
+        assert!(batch.errors().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_batch_reports_per_item() {
+        let items = [&b"SGVsbG8="[..], b"V29y!GQ=", b"", b"Zm9vYmFy"];
+        let batch = decode_batch(items);
+
+        assert_eq!(batch.len(), 4);
+        assert_eq!(batch.get(0), Some(Ok(&b"Hello"[..])));
+        assert_eq!(batch.get(1), Some(Err(DecodeError::InvalidByte(4, b'!'))));
+        assert_eq!(batch.get(2), Some(Ok(&b""[..])));
+        assert_eq!(batch.get(3), Some(Ok(&b"foobar"[..])));
+        assert_eq!(batch.data(), b"Hellofoobar");
+        assert_eq!(batch.offsets(), &[0, 5, 5, 5, 11]);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_unpack() {
+        assert!(unpack(b"abcdef", &[0, 2, 6]).is_some());
+        assert_eq!(unpack(b"", &[0]).map(Iterator::count), Some(0));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_unpack_rejects_inconsistent_offsets() {
+        assert!(unpack(b"abcdef", &[]).is_none());
+        assert!(unpack(b"abcdef", &[1, 2, 6]).is_none());
+        assert!(unpack(b"abcdef", &[0, 4, 2, 6]).is_none());
+        assert!(unpack(b"abcdef", &[0, 2, 5]).is_none());
+        assert!(unpack(b"abcdef", &[0, 2, 7]).is_none());
+        assert!(unpack(b"abc", &[0, 2, 6]).is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_batch_matches_single_calls() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(600).collect();
+        let items: Vec<&[u8]> = data.chunks(37).collect();
+
+        let encoded = encode_batch(items.iter().copied());
+        let decoded = decode_batch(
+            encoded
+                .offsets()
+                .windows(2)
+                .map(|pair| &encoded.data()[pair[0] as usize..pair[1] as usize]),
+        );
+
+        assert_eq!(decoded.data(), data);
+        for (index, item) in items.iter().enumerate() {
+            assert_eq!(decoded.get(index), Some(Ok(*item)));
+        }
+    }
+}
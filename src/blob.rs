@@ -0,0 +1,135 @@
+//! Read a `Blob` or `File` into base64 or a data URL, a promise-based replacement for
+//! `FileReader.readAsDataURL`. The blob is read through `blob.stream()` and encoded as each
+//! chunk arrives.
+
+use js_sys::{Promise, Reflect, Uint8Array};
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::data_url::data_url_prefix;
+use crate::{len, StreamEncoder};
+
+#[wasm_bindgen]
+extern "C" {
+    /// The subset of `Blob`, and so of `File`, needed to stream its bytes.
+    #[wasm_bindgen(js_name = Blob)]
+    pub type Blob;
+
+    #[wasm_bindgen(method, getter)]
+    fn size(this: &Blob) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = type)]
+    fn mime(this: &Blob) -> String;
+
+    #[wasm_bindgen(method)]
+    fn stream(this: &Blob) -> ReadableStream;
+
+    type ReadableStream;
+
+    #[wasm_bindgen(method, js_name = getReader)]
+    fn get_reader(this: &ReadableStream) -> ReadableStreamDefaultReader;
+
+    type ReadableStreamDefaultReader;
+
+    #[wasm_bindgen(method)]
+    fn read(this: &ReadableStreamDefaultReader) -> Promise;
+}
+
+/// [`blob_to_base64`] resolves to the padded base64 encoding of `blob`.
+#[wasm_bindgen]
+pub async fn blob_to_base64(blob: Blob) -> Result<String, JsValue> {
+    read_encoded(&blob).await
+}
+
+/// [`blob_to_data_url`] resolves to a base64 data URL of `blob`, typed with `blob.type` or,
+/// like `FileReader`, `application/octet-stream` when that is empty.
+#[wasm_bindgen]
+pub async fn blob_to_data_url(blob: Blob) -> Result<String, JsValue> {
+    let mime = match blob.mime() {
+        mime if mime.is_empty() => String::from("application/octet-stream"),
+        mime => mime,
+    };
+
+    let mut url = data_url_prefix(&mime, &[]);
+    url.push_str(&read_encoded(&blob).await?);
+    Ok(url)
+}
+
+async fn read_encoded(blob: &Blob) -> Result<String, JsValue> {
+    let mut ascii = Vec::with_capacity(len::encoded_len(blob.size() as usize, true).unwrap_or(0));
+    let mut encoder = StreamEncoder::new(&mut ascii);
+    let reader = blob.stream().get_reader();
+
+    loop {
+        let result = JsFuture::from(reader.read()).await?;
+        if Reflect::get(&result, &"done".into())?.is_truthy() {
+            break;
+        }
+
+        let chunk = Reflect::get(&result, &"value".into())?;
+        encoder.push(&chunk.unchecked_into::<Uint8Array>().to_vec());
+    }
+    encoder.finish();
+
+    // SAFETY: every byte written is a base64 symbol or `=`, so ascii.
+    Ok(unsafe { String::from_utf8_unchecked(ascii) })
+}
+
+#[cfg(test)]
+mod tests {
+    use js_sys::{Array, Object};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::engine::general_purpose::STANDARD;
+    use crate::engine::Engine;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(constructor, js_class = Blob)]
+        fn new(parts: &Array, options: &Object) -> Blob;
+    }
+
+    fn blob(parts: &[&[u8]], mime: &str) -> Result<Blob, JsValue> {
+        let options = Object::new();
+        Reflect::set(&options, &"type".into(), &mime.into())?;
+
+        let parts = parts.iter().map(|part| Uint8Array::from(*part)).collect();
+        Ok(Blob::new(&parts, &options))
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_blob_to_base64() -> Result<(), JsValue> {
+        let encoded = blob_to_base64(blob(&[b"foo", b"b"], "text/plain")?).await?;
+        assert_eq!(encoded, "Zm9vYg==");
+
+        assert_eq!(blob_to_base64(blob(&[], "")?).await?, "");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_blob_to_base64_across_chunks() -> Result<(), JsValue> {
+        // Streams hand out chunks of up to 64 KiB, and 65536 % 3 == 1, so groups straddle
+        // chunk boundaries.
+        let data: Vec<u8> = (0..=255u8).cycle().take(200_001).collect();
+        let parts = [&data[..70_001], &data[70_001..130_000], &data[130_000..]];
+
+        let encoded = blob_to_base64(blob(&parts, "")?).await?;
+        assert_eq!(encoded, STANDARD.encode(&data));
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_blob_to_data_url() -> Result<(), JsValue> {
+        let url = blob_to_data_url(blob(&[b"howdy"], "text/plain")?).await?;
+        assert_eq!(url, "data:text/plain;base64,aG93ZHk=");
+
+        let url = blob_to_data_url(blob(&[b"howdy"], "")?).await?;
+        assert_eq!(url, "data:application/octet-stream;base64,aG93ZHk=");
+
+        let url = blob_to_data_url(blob(&[], "")?).await?;
+        assert_eq!(url, "data:application/octet-stream;base64,");
+        Ok(())
+    }
+}
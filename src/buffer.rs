@@ -0,0 +1,216 @@
+//! A reusable pair of buffers in wasm memory that JS reads and writes through views, so large
+//! payloads are never copied across the boundary.
+
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+
+use crate::{
+    decode_in_place, decode_into, decoded_len, encode_into, len, DecodeError, EncodeError,
+};
+
+/// [`Base64Buffer`] owns an input and an output buffer in wasm memory. JS fills the view
+/// returned by `input(len)`, calls `encode()` or `decode()`, then reads the view returned by
+/// `output()`.
+///
+/// A view is a window onto wasm memory and is detached whenever that memory grows, which any
+/// allocating call into wasm may do. So views are made fresh on every call and should be
+/// fetched again after calling into wasm, rather than kept. `encode()` and `decode()` only
+/// allocate when the output outgrows what was reserved.
+#[derive(Debug, Default)]
+#[wasm_bindgen]
+pub struct Base64Buffer {
+    input: Vec<u8>,
+    output: Vec<u8>,
+    output_len: usize,
+}
+
+impl Base64Buffer {
+    /// [`Base64Buffer::with_capacity`] reserves room for `capacity` input bytes and for
+    /// their encoding, so neither `encode` nor `decode` of that much input allocates.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let output_capacity = len::encoded_len(capacity, true)
+            .expect("usize overflow when calculating encoded length");
+
+        Self {
+            input: Vec::with_capacity(capacity),
+            output: Vec::with_capacity(output_capacity),
+            output_len: 0,
+        }
+    }
+
+    /// [`Base64Buffer::input_mut`] resizes the input to `len` bytes and returns it.
+    pub fn input_mut(&mut self, len: usize) -> &mut [u8] {
+        self.input.resize(len, 0);
+        self.output_len = 0;
+        &mut self.input
+    }
+
+    /// [`Base64Buffer::output`] returns the result of the last `encode` or `decode`.
+    pub fn output(&self) -> &[u8] {
+        &self.output[..self.output_len]
+    }
+
+    /// [`Base64Buffer::encode`] encodes the input into the output and returns its length.
+    pub fn encode(&mut self) -> Result<usize, EncodeError> {
+        let len = len::encoded_len(self.input.len(), true).ok_or(EncodeError::LengthOverflow)?;
+        self.output.resize(len, 0);
+
+        self.output_len = encode_into(&self.input, &mut self.output)?;
+        Ok(self.output_len)
+    }
+
+    /// [`Base64Buffer::decode`] decodes the input into the output and returns its length.
+    pub fn decode(&mut self) -> Result<usize, DecodeError> {
+        self.output.resize(decoded_len(self.input.len()), 0);
+        self.output_len = 0;
+
+        self.output_len = decode_into(&self.input, &mut self.output)?;
+        Ok(self.output_len)
+    }
+
+    /// [`Base64Buffer::decode_in_place`] decodes the input over itself and makes it the
+    /// output, so decoding needs no second buffer. On error the input is left partly
+    /// overwritten.
+    pub fn decode_in_place(&mut self) -> Result<usize, DecodeError> {
+        self.output_len = 0;
+
+        let len = decode_in_place(&mut self.input)?.len();
+        self.input.truncate(len);
+        core::mem::swap(&mut self.input, &mut self.output);
+
+        self.output_len = len;
+        Ok(len)
+    }
+}
+
+#[wasm_bindgen]
+impl Base64Buffer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_capacity(capacity)
+    }
+
+    /// Resizes the input to `len` bytes and returns a view to fill.
+    #[wasm_bindgen(js_name = input)]
+    pub fn input_js(&mut self, len: usize) -> Uint8Array {
+        let input = self.input_mut(len);
+
+        // SAFETY: the view borrows `self.input`, which lives as long as `self`. JS is told
+        // to drop it before the next call that may grow memory or resize the input.
+        unsafe { Uint8Array::view(input) }
+    }
+
+    /// Returns a view of the result of the last `encode()` or `decode()`.
+    #[wasm_bindgen(js_name = output)]
+    pub fn output_js(&self) -> Uint8Array {
+        // SAFETY: as in `input`, the view must not outlive the next call into wasm.
+        unsafe { Uint8Array::view(self.output()) }
+    }
+
+    /// The address of the input in wasm memory, for callers building their own views.
+    #[wasm_bindgen(getter = input_ptr)]
+    pub fn input_ptr_js(&self) -> *const u8 {
+        self.input.as_ptr()
+    }
+
+    /// The address of the output in wasm memory, for callers building their own views.
+    #[wasm_bindgen(getter = output_ptr)]
+    pub fn output_ptr_js(&self) -> *const u8 {
+        self.output.as_ptr()
+    }
+
+    #[wasm_bindgen(getter = output_len)]
+    pub fn output_len_js(&self) -> usize {
+        self.output_len
+    }
+
+    #[wasm_bindgen(js_name = encode)]
+    pub fn encode_js(&mut self) -> Result<usize, JsValue> {
+        Ok(self.encode()?)
+    }
+
+    #[wasm_bindgen(js_name = decode)]
+    pub fn decode_js(&mut self) -> Result<usize, JsValue> {
+        Ok(self.decode()?)
+    }
+
+    #[wasm_bindgen(js_name = decode_in_place)]
+    pub fn decode_in_place_js(&mut self) -> Result<usize, JsValue> {
+        Ok(self.decode_in_place()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_round_trip() -> Result<(), DecodeError> {
+        let mut buffer = Base64Buffer::with_capacity(64);
+
+        buffer.input_mut(11).copy_from_slice(b"Hello World");
+        assert_eq!(buffer.encode(), Ok(16));
+        assert_eq!(buffer.output(), b"SGVsbG8gV29ybGQ=");
+
+        buffer.input_mut(16).copy_from_slice(b"SGVsbG8gV29ybGQ=");
+        assert_eq!(buffer.decode()?, 11);
+        assert_eq!(buffer.output(), b"Hello World");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_reserved_capacity_is_reused() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(300).collect();
+        let mut buffer = Base64Buffer::with_capacity(data.len());
+        let (input, output) = (buffer.input.as_ptr(), buffer.output.as_ptr());
+
+        buffer.input_mut(data.len()).copy_from_slice(&data);
+        buffer.encode().unwrap();
+        assert_eq!(
+            (buffer.input.as_ptr(), buffer.output.as_ptr()),
+            (input, output)
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_in_place() -> Result<(), DecodeError> {
+        let mut buffer = Base64Buffer::with_capacity(0);
+
+        buffer.input_mut(16).copy_from_slice(b"SGVsbG8gV29ybGQ=");
+        let input = buffer.input.as_ptr();
+        assert_eq!(buffer.decode_in_place()?, 11);
+        assert_eq!(buffer.output(), b"Hello World");
+        assert_eq!(buffer.output.as_ptr(), input);
+
+        buffer.input_mut(4).copy_from_slice(b"SGk=");
+        assert_eq!(buffer.decode_in_place()?, 2);
+        assert_eq!(buffer.output(), b"Hi");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_views_after_memory_growth() {
+        let mut buffer = Base64Buffer::with_capacity(16);
+
+        let stale = buffer.input_js(11);
+        stale.copy_from(b"Hello World");
+        core::arch::wasm32::memory_grow::<0>(1);
+        assert_eq!(stale.length(), 0);
+
+        assert_eq!(buffer.input_js(11).to_vec(), b"Hello World");
+        assert_eq!(buffer.encode(), Ok(16));
+        assert_eq!(buffer.output_js().to_vec(), b"SGVsbG8gV29ybGQ=");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_error_clears_output() {
+        let mut buffer = Base64Buffer::with_capacity(16);
+
+        buffer.input_mut(8).copy_from_slice(b"SGVs!G8=");
+        assert_eq!(buffer.decode(), Err(DecodeError::InvalidByte(4, b'!')));
+        assert_eq!(buffer.output(), b"");
+    }
+}
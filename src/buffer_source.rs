@@ -0,0 +1,157 @@
+//! JS bindings that take any `BufferSource`: an `ArrayBuffer`, a `SharedArrayBuffer`, or a view
+//! on one such as a `DataView`, any typed array or a Node `Buffer`.
+
+use js_sys::{ArrayBuffer, Object, Reflect, SharedArrayBuffer, Uint8Array};
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::{decode_into, decoded_len, encode, encode_into, encode_to_utf8, len};
+
+#[wasm_bindgen]
+extern "C" {
+    /// The `ArrayBuffer.isView` check, true for typed arrays and `DataView`s.
+    #[wasm_bindgen(js_namespace = ArrayBuffer, js_name = isView)]
+    fn is_view(value: &JsValue) -> bool;
+
+    /// The fields every `ArrayBufferView` shares.
+    type ArrayBufferView;
+
+    #[wasm_bindgen(method, getter)]
+    fn buffer(this: &ArrayBufferView) -> Object;
+
+    #[wasm_bindgen(method, getter, js_name = byteOffset)]
+    fn byte_offset(this: &ArrayBufferView) -> u32;
+
+    #[wasm_bindgen(method, getter, js_name = byteLength)]
+    fn byte_length(this: &ArrayBufferView) -> u32;
+}
+
+/// [`byte_view`] returns a `Uint8Array` over exactly the bytes of `source`, respecting the
+/// `byteOffset` and `byteLength` of a view. Nothing is copied.
+pub(crate) fn byte_view(source: &JsValue) -> Result<Uint8Array, JsValue> {
+    if let Some(bytes) = source.dyn_ref::<Uint8Array>() {
+        return Ok(bytes.clone());
+    }
+
+    if is_view(source) {
+        let view = source.unchecked_ref::<ArrayBufferView>();
+        return Ok(Uint8Array::new_with_byte_offset_and_length(
+            &view.buffer(),
+            view.byte_offset(),
+            view.byte_length(),
+        ));
+    }
+
+    if source.is_instance_of::<ArrayBuffer>() || is_shared_array_buffer(source) {
+        return Ok(Uint8Array::new(source));
+    }
+
+    Err(js_sys::TypeError::new("expected an ArrayBuffer, TypedArray or DataView").into())
+}
+
+/// [`is_shared_array_buffer`] checks for a `SharedArrayBuffer` without touching the global on
+/// pages that are not cross-origin isolated, where it is undefined.
+fn is_shared_array_buffer(value: &JsValue) -> bool {
+    Reflect::has(&js_sys::global(), &"SharedArrayBuffer".into()).unwrap_or(false)
+        && value.is_instance_of::<SharedArrayBuffer>()
+}
+
+/// [`encode_js`] converts any `BufferSource` into a base64-encoded byte array.
+#[wasm_bindgen(js_name = encode)]
+pub fn encode_js(data: &JsValue) -> Result<Vec<u8>, JsValue> {
+    encode(&byte_view(data)?.to_vec())
+}
+
+/// [`encode_to_utf8_js`] converts any `BufferSource` into a base64 string.
+#[wasm_bindgen(js_name = encode_to_utf8)]
+pub fn encode_to_utf8_js(data: &JsValue) -> Result<String, JsValue> {
+    encode_to_utf8(&byte_view(data)?.to_vec())
+}
+
+/// [`encode_into_js`] encodes any `BufferSource` into the front of another, returning the
+/// number of bytes written.
+#[wasm_bindgen(js_name = encode_into)]
+pub fn encode_into_js(data: &JsValue, out: &JsValue) -> Result<usize, JsValue> {
+    let data = byte_view(data)?.to_vec();
+    let out = byte_view(out)?;
+
+    let len = len::encoded_len(data.len(), true).unwrap_or(usize::MAX);
+    let mut ascii = vec![0u8; len.min(out.length() as usize)];
+    let written = encode_into(&data, &mut ascii)?;
+
+    out.subarray(0, written as u32).copy_from(&ascii[..written]);
+    Ok(written)
+}
+
+/// [`decode_into_js`] decodes ascii from any `BufferSource` into the front of another,
+/// returning the number of bytes written.
+#[wasm_bindgen(js_name = decode_into)]
+pub fn decode_into_js(ascii: &JsValue, out: &JsValue) -> Result<usize, JsValue> {
+    let ascii = byte_view(ascii)?.to_vec();
+    let out = byte_view(out)?;
+
+    let mut data = vec![0u8; decoded_len(ascii.len()).min(out.length() as usize)];
+    let written = decode_into(&ascii, &mut data)?;
+
+    out.subarray(0, written as u32).copy_from(&data[..written]);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use js_sys::{DataView, Float32Array};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::engine::general_purpose::STANDARD;
+    use crate::engine::Engine;
+
+    #[wasm_bindgen_test]
+    fn test_encode_views() -> Result<(), JsValue> {
+        let bytes = Uint8Array::from(&b"xxHello Worldyy"[..]);
+        let buffer = bytes.buffer();
+
+        assert_eq!(
+            encode_to_utf8_js(&bytes.subarray(2, 13))?,
+            "SGVsbG8gV29ybGQ="
+        );
+        assert_eq!(
+            encode_to_utf8_js(&DataView::new(&buffer, 2, 11))?,
+            "SGVsbG8gV29ybGQ="
+        );
+        assert_eq!(encode_to_utf8_js(&buffer)?, "eHhIZWxsbyBXb3JsZHl5");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_encode_float32_array() -> Result<(), JsValue> {
+        let floats = [1.0f32, -2.5, 0.1];
+        let bytes: Vec<u8> = floats.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+        let array = Float32Array::from(&floats[..]);
+        assert_eq!(encode_to_utf8_js(&array)?, STANDARD.encode(&bytes));
+        assert_eq!(
+            encode_to_utf8_js(&array.subarray(1, 3))?,
+            STANDARD.encode(&bytes[4..])
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_into_view() -> Result<(), JsValue> {
+        let ascii = Uint8Array::from(&b"..SGVsbG8gV29ybGQ="[..]).subarray(2, 18);
+        let out = Uint8Array::new_with_length(16);
+
+        let written = decode_into_js(&ascii, &DataView::new(&out.buffer(), 3, 12))?;
+        assert_eq!(written, 11);
+        assert_eq!(out.to_vec()[..3], [0; 3]);
+        assert_eq!(out.to_vec()[3..14], *b"Hello World");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rejects_other_values() {
+        assert!(byte_view(&JsValue::from(3)).is_err());
+        assert!(byte_view(&JsValue::from_str("SGVsbG8=")).is_err());
+    }
+}
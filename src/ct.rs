@@ -0,0 +1,375 @@
+//! Constant-time decoding for secret material such as private keys, HMAC secrets and session
+//! tokens.
+//!
+//! The SIMD kernels report the offending byte and branch to do so, and the scalar tails branch
+//! on every symbol. Here each symbol is mapped to its sextet with masks alone, so no branch or
+//! memory access depends on it. Only the input length and the trailing `=`, both public, steer
+//! control flow. Any invalid symbol surfaces as [`DecodeError::InvalidSymbols`], without saying
+//! which, once the whole input has been read.
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ptr;
+use core::sync::atomic::{compiler_fence, Ordering};
+#[cfg(feature = "alloc")]
+use core::{fmt, ops};
+
+use crate::alphabet::Alphabet;
+use crate::decode_chunk::decoded_len;
+use crate::DecodeError;
+
+/// [`SecretBytes`] owns decoded secret bytes and zeroes them when dropped.
+#[cfg(feature = "alloc")]
+pub struct SecretBytes(Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl ops::Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ops::DerefMut for SecretBytes {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes([REDACTED; {}])", self.0.len())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        zeroize(&mut self.0);
+    }
+}
+
+/// [`decode`] decodes `ascii` in `alphabet`, with or without canonical padding, into a buffer
+/// that is zeroed on drop.
+#[cfg(feature = "alloc")]
+pub fn decode(ascii: &[u8], alphabet: Alphabet) -> Result<SecretBytes, DecodeError> {
+    let symbols = strip_padding(ascii)?;
+
+    let mut out = SecretBytes(vec![0; decoded_len(symbols.len())]);
+    decode_symbols(symbols, &mut out, alphabet)?;
+
+    Ok(out)
+}
+
+/// [`decode_into`] decodes `ascii` in `alphabet` into the front of `out` and returns the number
+/// of bytes written. On error nothing decoded is left behind in `out`.
+pub fn decode_into(ascii: &[u8], out: &mut [u8], alphabet: Alphabet) -> Result<usize, DecodeError> {
+    let symbols = strip_padding(ascii)?;
+    let len = decoded_len(symbols.len());
+
+    if out.len() < len {
+        return Err(DecodeError::OutputSliceTooSmall);
+    }
+
+    let out = &mut out[..len];
+    if let Err(err) = decode_symbols(symbols, out, alphabet) {
+        zeroize(out);
+        return Err(err);
+    }
+
+    Ok(len)
+}
+
+/// [`zeroize`] overwrites `buf` with zeros in a way the compiler cannot elide.
+pub fn zeroize(buf: &mut [u8]) {
+    for byte in buf.iter_mut() {
+        // SAFETY: `byte` is a valid, aligned `&mut u8`.
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// [`strip_padding`] only looks at the trailing `=`, whose count follows from the public
+/// length of the secret.
+fn strip_padding(ascii: &[u8]) -> Result<&[u8], DecodeError> {
+    let symbols = match ascii {
+        [rest @ .., b'=', b'='] | [rest @ .., b'='] => rest,
+        _ => ascii,
+    };
+
+    if symbols.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength(symbols.len()));
+    }
+
+    if symbols.len() != ascii.len() && !ascii.len().is_multiple_of(4) {
+        return Err(DecodeError::InvalidPadding);
+    }
+
+    Ok(symbols)
+}
+
+/// [`decode_symbols`] fills `out`, exactly `decoded_len(symbols.len())` bytes, folding every
+/// symbol's validity and the trailing bits into one flag checked at the end.
+fn decode_symbols(symbols: &[u8], out: &mut [u8], alphabet: Alphabet) -> Result<(), DecodeError> {
+    let (sym62, sym63) = match alphabet {
+        Alphabet::Standard => (b'+', b'/'),
+        Alphabet::UrlSafe => (b'-', b'_'),
+    };
+
+    let mut invalid = 0u32;
+
+    for (group, out) in symbols.chunks(4).zip(out.chunks_mut(3)) {
+        let mut bits = 0u32;
+        for (i, &symbol) in group.iter().enumerate() {
+            let (sextet, bad) = sextet(symbol, sym62, sym63);
+            bits |= sextet << (18 - 6 * i);
+            invalid |= bad;
+        }
+
+        // A short last group must leave the bits past its final byte clear.
+        let trailing = match group.len() {
+            2 => bits & 0xFFFF,
+            3 => bits & 0xFF,
+            _ => 0,
+        };
+        invalid |= nonzero(trailing);
+
+        let bytes = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+        out.copy_from_slice(&bytes[..out.len()]);
+    }
+
+    match core::hint::black_box(invalid) {
+        0 => Ok(()),
+        _ => Err(DecodeError::InvalidSymbols),
+    }
+}
+
+/// [`sextet`] returns a symbol's 6-bit value, and `1` alongside it if the symbol is outside
+/// the alphabet.
+#[inline(always)]
+fn sextet(symbol: u8, sym62: u8, sym63: u8) -> (u32, u32) {
+    let c = symbol as i32;
+
+    let upper = in_range(c, b'A', b'Z');
+    let lower = in_range(c, b'a', b'z');
+    let digit = in_range(c, b'0', b'9');
+    let is62 = in_range(c, sym62, sym62);
+    let is63 = in_range(c, sym63, sym63);
+
+    let value = (upper & (c - b'A' as i32))
+        | (lower & (c - b'a' as i32 + 26))
+        | (digit & (c - b'0' as i32 + 52))
+        | (is62 & 62)
+        | (is63 & 63);
+    let valid = upper | lower | digit | is62 | is63;
+
+    (value as u32, !valid as u32 & 1)
+}
+
+/// [`in_range`] returns `-1` if `lo <= c <= hi`, and `0` otherwise. Both differences are
+/// negative only inside the range, and `>> 8` smears the sign across the word.
+#[inline(always)]
+fn in_range(c: i32, lo: u8, hi: u8) -> i32 {
+    ((lo as i32 - 1 - c) & (c - hi as i32 - 1)) >> 8
+}
+
+/// [`nonzero`] returns `1` if `x` is nonzero, and `0` otherwise.
+#[inline(always)]
+fn nonzero(x: u32) -> u32 {
+    (x | x.wrapping_neg()) >> 31
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+    use crate::engine::Engine;
+
+    /// [`XorShift`] is a small deterministic generator for test inputs.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn fill(&mut self, buf: &mut [u8]) {
+            for byte in buf {
+                *byte = self.next() as u8;
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_matches_engine() {
+        let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+        let mut data = [0u8; 100];
+        rng.fill(&mut data);
+
+        for len in 0..data.len() {
+            let bytes = &data[..len];
+
+            let padded = STANDARD.encode(bytes);
+            let decoded = decode(padded.as_bytes(), Alphabet::Standard).unwrap();
+            assert_eq!(&*decoded, bytes);
+
+            let url_safe = URL_SAFE_NO_PAD.encode(bytes);
+            let decoded = decode(url_safe.as_bytes(), Alphabet::UrlSafe).unwrap();
+            assert_eq!(&*decoded, bytes);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_sextet_covers_every_byte() {
+        for alphabet in [Alphabet::Standard, Alphabet::UrlSafe] {
+            let (sym62, sym63) = match alphabet {
+                Alphabet::Standard => (b'+', b'/'),
+                Alphabet::UrlSafe => (b'-', b'_'),
+            };
+
+            for byte in 0..=255u8 {
+                let (sextet, bad) = sextet(byte, sym62, sym63);
+
+                match alphabet.sextet(byte) {
+                    Some(expected) => assert_eq!((sextet, bad), (expected as u32, 0)),
+                    None => assert_eq!((sextet, bad), (0, 1)),
+                }
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_errors_withhold_position() {
+        assert_eq!(
+            decode(b"SGVsbG8*V29ybGQ=", Alphabet::Standard).unwrap_err(),
+            DecodeError::InvalidSymbols
+        );
+        assert_eq!(
+            decode(b"SGVsbG8gV29ybGR=", Alphabet::Standard).unwrap_err(),
+            DecodeError::InvalidSymbols
+        );
+        assert_eq!(
+            decode(b"SGVsbG8-V29ybGQ=", Alphabet::Standard).unwrap_err(),
+            DecodeError::InvalidSymbols
+        );
+        assert_eq!(
+            decode(b"SGVsb", Alphabet::Standard).unwrap_err(),
+            DecodeError::InvalidLength(5)
+        );
+        assert_eq!(
+            decode(b"SGVsbG8==", Alphabet::Standard).unwrap_err(),
+            DecodeError::InvalidPadding
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_into_zeroes_on_error() {
+        let mut out = [0xAAu8; 16];
+
+        assert_eq!(
+            decode_into(b"SGVsbG8gV29ybGQ*", &mut out, Alphabet::Standard),
+            Err(DecodeError::InvalidSymbols)
+        );
+        assert_eq!(&out[..12], &[0; 12]);
+        assert_eq!(&out[12..], &[0xAA; 4]);
+
+        assert_eq!(
+            decode_into(b"SGVsbG8gV29ybGQ=", &mut out, Alphabet::Standard),
+            Ok(11)
+        );
+        assert_eq!(&out[..11], b"Hello World");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_secret_bytes_debug_is_redacted() {
+        let secret = decode(b"c2VjcmV0", Alphabet::Standard).unwrap();
+        assert_eq!(format!("{secret:?}"), "SecretBytes([REDACTED; 6])");
+    }
+
+    /// [`now`] reads `performance.now()`, in milliseconds.
+    #[cfg(feature = "std")]
+    fn now() -> f64 {
+        let performance = js_sys::Reflect::get(&js_sys::global(), &"performance".into()).unwrap();
+        let now = js_sys::Reflect::get(&performance, &"now".into()).unwrap();
+        let now: js_sys::Function = now.into();
+
+        now.call0(&performance).unwrap().as_f64().unwrap()
+    }
+
+    /// [`Welch`] accumulates one class of timings for Welch's t-test.
+    #[cfg(feature = "std")]
+    #[derive(Default)]
+    struct Welch {
+        n: f64,
+        mean: f64,
+        m2: f64,
+    }
+
+    #[cfg(feature = "std")]
+    impl Welch {
+        fn push(&mut self, x: f64) {
+            self.n += 1.0;
+            let delta = x - self.mean;
+            self.mean += delta / self.n;
+            self.m2 += delta * (x - self.mean);
+        }
+
+        fn t(&self, other: &Self) -> f64 {
+            let var = self.m2 / (self.n - 1.0) / self.n + other.m2 / (other.n - 1.0) / other.n;
+            (self.mean - other.mean) / var.sqrt()
+        }
+    }
+
+    /// A dudect-style check: time decoding a fixed secret against random ones, interleaved
+    /// at random, and fail if Welch's t-test tells the two classes apart. It is timing-based
+    /// and wants a quiet machine, so it only runs when asked for.
+    #[cfg(feature = "std")]
+    #[wasm_bindgen_test]
+    #[ignore = "timing-sensitive, run with --ignored"]
+    fn test_dudect_fixed_vs_random() {
+        const SECRET_LEN: usize = 96;
+        const BATCH: usize = 256;
+        const MEASUREMENTS: usize = 2_000;
+
+        let mut rng = XorShift(0xD1B5_4A32_D192_ED03);
+        let fixed = STANDARD.encode([0u8; SECRET_LEN]);
+        let mut random = Vec::with_capacity(BATCH);
+        let mut secret = [0u8; SECRET_LEN];
+        let mut out = [0u8; SECRET_LEN];
+        let mut classes = [Welch::default(), Welch::default()];
+
+        for _ in 0..MEASUREMENTS {
+            let class = (rng.next() & 1) as usize;
+
+            random.clear();
+            for _ in 0..BATCH {
+                rng.fill(&mut secret);
+                random.push(STANDARD.encode(secret));
+            }
+
+            let start = now();
+            for input in &random {
+                let input = match class {
+                    0 => fixed.as_bytes(),
+                    _ => input.as_bytes(),
+                };
+                core::hint::black_box(decode_into(input, &mut out, Alphabet::Standard)).unwrap();
+            }
+            classes[class].push(now() - start);
+        }
+
+        let t = classes[0].t(&classes[1]);
+        assert!(t.abs() < 10.0, "timing leak, t = {t}");
+    }
+}
@@ -0,0 +1,481 @@
+//! Build and parse `data:` URLs ([RFC 2397]), parsing as the WHATWG fetch spec's
+//! [data: URL processor] does.
+//!
+//! [RFC 2397]: https://www.rfc-editor.org/rfc/rfc2397
+//! [data: URL processor]: https://fetch.spec.whatwg.org/#data-url-processor
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use js_sys::{Object, Reflect, Uint8Array};
+#[cfg(feature = "std")]
+use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "std")]
+use wasm_bindgen::JsValue;
+
+#[cfg(feature = "std")]
+use crate::buffer_source::byte_view;
+use crate::{decode_to, encode_to_string, DecodeError};
+#[cfg(feature = "std")]
+use crate::{entries_object, string_entries};
+
+/// [`DataUrl`] is a parsed `data:` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataUrl {
+    /// The lowercase `type/subtype` essence of the media type.
+    pub mime: String,
+    /// The media type parameters in order, names lowercased, values unquoted.
+    pub params: Vec<(String, String)>,
+    /// The decoded body.
+    pub bytes: Vec<u8>,
+}
+
+/// [`DataUrlError`] describes why a `data:` URL could not be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataUrlError {
+    /// The URL does not start with `data:`.
+    NotDataUrl,
+    /// There is no `,` between the media type and the body.
+    MissingComma,
+    /// The body is flagged `;base64` but is not forgiving base64.
+    InvalidBase64(DecodeError),
+}
+
+impl fmt::Display for DataUrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotDataUrl => write!(f, "not a data: URL"),
+            Self::MissingComma => write!(f, "missing ',' after the media type"),
+            Self::InvalidBase64(err) => write!(f, "invalid base64 body: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DataUrlError {}
+
+#[cfg(feature = "std")]
+impl From<DataUrlError> for JsValue {
+    fn from(err: DataUrlError) -> Self {
+        Self::from_str(&err.to_string())
+    }
+}
+
+/// [`to_data_url_js`] builds a base64 `data:` URL, taking parameters from the entries of
+/// `params`.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = to_data_url)]
+pub fn to_data_url_js(
+    bytes: &JsValue,
+    mime: &str,
+    params: Option<Object>,
+) -> Result<String, JsValue> {
+    let bytes = byte_view(bytes)?.to_vec();
+
+    let params = string_entries(params);
+    let params: Vec<(&str, &str)> = params
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+
+    Ok(to_data_url(&bytes, mime, &params))
+}
+
+/// [`parse_data_url_js`] parses a `data:` URL into `{ mime, params, bytes }`.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = parse_data_url)]
+pub fn parse_data_url_js(url: &str) -> Result<Object, JsValue> {
+    let DataUrl {
+        mime,
+        params,
+        bytes,
+    } = parse_data_url(url)?;
+
+    let params = entries_object(&params)?;
+
+    let parsed = Object::new();
+    Reflect::set(&parsed, &"mime".into(), &mime.into())?;
+    Reflect::set(&parsed, &"params".into(), &params)?;
+    Reflect::set(
+        &parsed,
+        &"bytes".into(),
+        &Uint8Array::from(bytes.as_slice()),
+    )?;
+
+    Ok(parsed)
+}
+
+/// [`to_data_url`] builds `data:<mime>;<name>=<value>...;base64,<bytes>`. Values that are not
+/// tokens are quoted.
+pub fn to_data_url(bytes: &[u8], mime: &str, params: &[(&str, &str)]) -> String {
+    let mut url = data_url_prefix(mime, params);
+    encode_to_string(bytes, &mut url);
+    url
+}
+
+/// [`data_url_prefix`] is everything [`to_data_url`] writes before the encoded bytes.
+pub(crate) fn data_url_prefix(mime: &str, params: &[(&str, &str)]) -> String {
+    let mut url = String::from("data:");
+    url.push_str(mime);
+
+    for (name, value) in params {
+        url.push(';');
+        url.push_str(name);
+        url.push('=');
+
+        if !value.is_empty() && value.chars().all(is_token) {
+            url.push_str(value);
+        } else {
+            url.push('"');
+            for c in value.chars() {
+                if c == '"' || c == '\\' {
+                    url.push('\\');
+                }
+                url.push(c);
+            }
+            url.push('"');
+        }
+    }
+
+    url.push_str(";base64,");
+    url
+}
+
+/// [`parse_data_url`] parses a `data:` URL. Without a valid media type it defaults to
+/// `text/plain;charset=US-ASCII`, a `;base64` body is forgiving-base64 decoded and any other
+/// body is percent-decoded.
+pub fn parse_data_url(url: &str) -> Result<DataUrl, DataUrlError> {
+    // What the URL parser does before the data: URL processor sees the URL.
+    let url: String = url
+        .trim_matches(|c: char| c <= ' ')
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+
+    let rest = match url.get(..5) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("data:") => &url[5..],
+        _ => return Err(DataUrlError::NotDataUrl),
+    };
+    let rest = rest.split_once('#').map_or(rest, |(rest, _fragment)| rest);
+
+    let (mime, body) = rest.split_once(',').ok_or(DataUrlError::MissingComma)?;
+    let mut mime = mime.trim_matches(is_ascii_whitespace);
+    let mut bytes = percent_decode(body.as_bytes());
+
+    if let Some(stripped) = strip_base64_flag(mime) {
+        bytes = forgiving_decode(&bytes).map_err(DataUrlError::InvalidBase64)?;
+        mime = stripped;
+    }
+
+    let mime = match mime.starts_with(';') {
+        true => parse_mime(&["text/plain", mime].concat()),
+        false => parse_mime(mime),
+    };
+
+    let (mime, params) = mime.unwrap_or_else(|| {
+        let params = vec![("charset".to_string(), "US-ASCII".to_string())];
+        ("text/plain".to_string(), params)
+    });
+
+    Ok(DataUrl {
+        mime,
+        params,
+        bytes,
+    })
+}
+
+/// [`strip_base64_flag`] removes a trailing `;` + spaces + `base64`, matched ASCII
+/// case-insensitively, and returns what precedes the `;`.
+fn strip_base64_flag(mime: &str) -> Option<&str> {
+    let split = mime.len().checked_sub(6)?;
+    let (head, flag) = (mime.get(..split)?, &mime[split..]);
+
+    if !flag.eq_ignore_ascii_case("base64") {
+        return None;
+    }
+
+    head.trim_end_matches(' ').strip_suffix(';')
+}
+
+/// [`forgiving_decode`] is the WHATWG forgiving-base64 decode: ASCII whitespace is ignored,
+/// padding is optional and trailing bits are discarded.
+fn forgiving_decode(ascii: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut symbols: Vec<u8> = ascii
+        .iter()
+        .copied()
+        .filter(|&b| !is_ascii_whitespace(b as char))
+        .collect();
+
+    if symbols.len().is_multiple_of(4) {
+        let unpadded = match symbols.as_slice() {
+            [p @ .., b'=', b'='] | [p @ .., b'='] | p => p.len(),
+        };
+        symbols.truncate(unpadded);
+    }
+
+    if symbols.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength(symbols.len()));
+    }
+
+    if let Some(&b'=') = symbols.last() {
+        return Err(DecodeError::InvalidPadding);
+    }
+
+    let mut data = Vec::new();
+    decode_to(&symbols, &mut data)?;
+    Ok(data)
+}
+
+/// [`percent_decode`] replaces each `%XX` with the byte it names, leaving any other `%` alone.
+fn percent_decode(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+
+    while i < input.len() {
+        let escaped = match input[i..] {
+            [b'%', hi, lo, ..] => hex(hi).zip(hex(lo)).map(|(hi, lo)| hi << 4 | lo),
+            _ => None,
+        };
+
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(input[i]);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+fn hex(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|d| d as u8)
+}
+
+/// [`parse_mime`] is the WHATWG MIME type parser, returning the essence and parameters, or
+/// `None` on failure.
+fn parse_mime(input: &str) -> Option<(String, Vec<(String, String)>)> {
+    let input = input.trim_matches(is_http_whitespace);
+
+    let (ty, rest) = input.split_once('/')?;
+    let (subtype, mut rest) = rest.split_once(';').unwrap_or((rest, ""));
+    let subtype = subtype.trim_end_matches(is_http_whitespace);
+
+    let is_token_str = |s: &str| !s.is_empty() && s.chars().all(is_token);
+    if !is_token_str(ty) || !is_token_str(subtype) {
+        return None;
+    }
+
+    let essence = [ty, "/", subtype].concat().to_ascii_lowercase();
+    let mut params: Vec<(String, String)> = Vec::new();
+
+    while !rest.is_empty() {
+        let param = rest.trim_start_matches(is_http_whitespace);
+        let name_end = param.find([';', '=']).unwrap_or(param.len());
+        let name = param[..name_end].to_ascii_lowercase();
+
+        let after_name = &param[name_end..];
+        let Some(after_eq) = after_name.strip_prefix('=') else {
+            rest = after_name.strip_prefix(';').unwrap_or("");
+            continue;
+        };
+
+        let value = match after_eq.strip_prefix('"') {
+            Some(quoted) => {
+                let (value, after) = unquote(quoted);
+                rest = after.split_once(';').map_or("", |(_, after)| after);
+                value
+            }
+            None => {
+                let (value, after) = after_eq.split_once(';').unwrap_or((after_eq, ""));
+                rest = after;
+
+                let value = value.trim_end_matches(is_http_whitespace);
+                if value.is_empty() {
+                    continue;
+                }
+                value.to_string()
+            }
+        };
+
+        if is_token_str(&name)
+            && value.chars().all(is_quoted_string_char)
+            && !params.iter().any(|(existing, _)| *existing == name)
+        {
+            params.push((name, value));
+        }
+    }
+
+    Some((essence, params))
+}
+
+/// [`unquote`] reads an HTTP quoted-string whose opening `"` was already consumed, returning
+/// its value and what follows the closing `"`.
+fn unquote(input: &str) -> (String, &str) {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return (value, &input[i + 1..]),
+            '\\' => match chars.next() {
+                Some((_, escaped)) => value.push(escaped),
+                None => value.push('\\'),
+            },
+            c => value.push(c),
+        }
+    }
+
+    (value, "")
+}
+
+fn is_token(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+fn is_quoted_string_char(c: char) -> bool {
+    matches!(c, '\t' | ' '..='~' | '\u{80}'..='\u{ff}')
+}
+
+fn is_http_whitespace(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | ' ')
+}
+
+fn is_ascii_whitespace(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\x0c' | '\r' | ' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn parsed(mime: &str, params: &[(&str, &str)], bytes: &[u8]) -> DataUrl {
+        DataUrl {
+            mime: mime.to_string(),
+            params: params
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_to_data_url() {
+        assert_eq!(
+            to_data_url(b"hello", "text/plain", &[("charset", "utf-8")]),
+            "data:text/plain;charset=utf-8;base64,aGVsbG8="
+        );
+        assert_eq!(
+            to_data_url(b"", "text/plain", &[("title", "a \"b\"")]),
+            "data:text/plain;title=\"a \\\"b\\\"\";base64,"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_round_trip() -> Result<(), DataUrlError> {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let url = to_data_url(&bytes, "model/gltf-binary", &[("name", "mesh one")]);
+
+        assert_eq!(
+            parse_data_url(&url)?,
+            parsed("model/gltf-binary", &[("name", "mesh one")], &bytes)
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_mime_defaults() -> Result<(), DataUrlError> {
+        let default = [("charset", "US-ASCII")];
+
+        assert_eq!(
+            parse_data_url("data:,x")?,
+            parsed("text/plain", &default, b"x")
+        );
+        assert_eq!(
+            parse_data_url("data:;charset=utf-8,x")?,
+            parsed("text/plain", &[("charset", "utf-8")], b"x")
+        );
+        assert_eq!(
+            parse_data_url("data:text,x")?,
+            parsed("text/plain", &default, b"x")
+        );
+        assert_eq!(
+            parse_data_url("DATA: Text/HTML ;A=1;a=2;b;c=\"q\\\"d\" ,x")?,
+            parsed("text/html", &[("a", "1"), ("c", "q\"d")], b"x")
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_base64_flag() -> Result<(), DataUrlError> {
+        let default = [("charset", "US-ASCII")];
+
+        assert_eq!(
+            parse_data_url("data:;base64,Zm9v")?,
+            parsed("text/plain", &default, b"foo")
+        );
+        assert_eq!(
+            parse_data_url("data:image/png;  BASE64,Zm9v")?,
+            parsed("image/png", &[], b"foo")
+        );
+        // Not a flag: the parameter stays and the body is not decoded.
+        assert_eq!(
+            parse_data_url("data:image/png;x=base64,Zm9v")?,
+            parsed("image/png", &[("x", "base64")], b"Zm9v")
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_forgiving_base64() -> Result<(), DataUrlError> {
+        let foo = |url| parse_data_url(url).map(|parsed| parsed.bytes);
+
+        assert_eq!(foo("data:;base64, Zm 9v\n")?, b"foo");
+        assert_eq!(foo("data:;base64,Zm8")?, b"fo");
+        assert_eq!(foo("data:;base64,Zm8=")?, b"fo");
+        assert_eq!(foo("data:;base64,Zm%38=")?, b"fo");
+        assert_eq!(foo("data:;base64,Zh==")?, b"f");
+        assert_eq!(foo("data:;base64,Zm8=#fragment")?, b"fo");
+        assert!(matches!(
+            foo("data:;base64,Zm9=="),
+            Err(DataUrlError::InvalidBase64(_))
+        ));
+        assert!(matches!(
+            foo("data:;base64,Zg="),
+            Err(DataUrlError::InvalidBase64(_))
+        ));
+        assert!(matches!(
+            foo("data:;base64,Zm9vY"),
+            Err(DataUrlError::InvalidBase64(_))
+        ));
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_percent_decoding() -> Result<(), DataUrlError> {
+        assert_eq!(parse_data_url("data:,a%20b%zz%4")?.bytes, b"a b%zz%4");
+        assert_eq!(parse_data_url("data:,%E2%9C%93")?.bytes, "✓".as_bytes());
+        assert_eq!(parse_data_url("data:,\u{2713}")?.bytes, "✓".as_bytes());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_errors() {
+        assert_eq!(parse_data_url("http://x,y"), Err(DataUrlError::NotDataUrl));
+        assert_eq!(parse_data_url("dat"), Err(DataUrlError::NotDataUrl));
+        assert_eq!(
+            parse_data_url("data:text/plain"),
+            Err(DataUrlError::MissingComma)
+        );
+    }
+}
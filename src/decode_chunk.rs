@@ -1,12 +1,13 @@
-use std::arch::wasm32::{
+use core::arch::wasm32::{
     i8x16_neg, i8x16_shr, u16x8_extend_high_u8x16, u16x8_extend_low_u8x16, u16x8_mul, u16x8_shr,
-    u16x8_splat, u8x16_add, u8x16_eq, u8x16_shr, u8x16_shuffle, u8x16_splat, u8x16_swizzle, v128,
-    v128_and, v128_or,
+    u16x8_splat, u8x16_add, u8x16_bitmask, u8x16_eq, u8x16_shr, u8x16_shuffle, u8x16_splat,
+    u8x16_swizzle, v128, v128_and, v128_or,
 };
 
 use crate::impl_v128::{u16x8_cycle, u16x8_to_array, u8x16_cycle, u8x16_load, u8x16_reduce_or};
+use crate::DecodeError;
 
-pub(super) fn decoded_len(input: usize) -> usize {
+pub(super) const fn decoded_len(input: usize) -> usize {
     let mod4 = input % 4;
     input / 4 * 3 + (mod4 - mod4 / 2)
 }
@@ -32,8 +33,9 @@ fn sextets(vectorized_ascii: v128, ascii_hashes: v128) -> v128 {
     )
 }
 
+/// [`invalid_lanes`] is nonzero in every lane that is not a symbol of the standard alphabet.
 #[inline]
-fn check_valid_characters(vectorized_ascii: v128) -> bool {
+fn invalid_lanes(vectorized_ascii: v128) -> v128 {
     let lut_lo = u8x16_load(&[
         0x15, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x13, 0x1A, 0x1B, 0x1B, 0x1B,
         0x1A,
@@ -47,17 +49,101 @@ fn check_valid_characters(vectorized_ascii: v128) -> bool {
     let lo = u8x16_swizzle(lut_lo, v128_and(vectorized_ascii, u8x16_splat(0x0F)));
     let hi = u8x16_swizzle(lut_hi, u8x16_shr(vectorized_ascii, 4));
 
-    !u8x16_reduce_or(v128_and(lo, hi))
+    v128_and(lo, hi)
+}
+
+#[inline]
+fn check_valid_characters(vectorized_ascii: v128) -> bool {
+    !u8x16_reduce_or(invalid_lanes(vectorized_ascii))
 }
 
+/// [`COMPACT_LUT`] maps an 8-lane keep mask to the swizzle indices that pack the kept lanes to
+/// the front. Unused indices are out of range, so those lanes come out zero.
+const COMPACT_LUT: [[u8; 8]; 256] = {
+    let mut lut = [[0x80; 8]; 256];
+    let mut mask = 0;
+
+    while mask < 256 {
+        let mut kept = 0;
+        let mut lane = 0;
+
+        while lane < 8 {
+            if mask & (1 << lane) != 0 {
+                lut[mask][kept] = lane as u8;
+                kept += 1;
+            }
+            lane += 1;
+        }
+
+        mask += 1;
+    }
+
+    lut
+};
+
+/// [`compact_chunk`] packs the lanes of `ascii` that are standard symbols to the front and
+/// returns them with their count. Each half is packed through [`COMPACT_LUT`] and the high
+/// half's indices are placed right after the low half's kept lanes.
 #[inline]
-pub(super) fn decode_chunk(ascii: &[u8; 16]) -> Result<v128, String> {
+pub(super) fn compact_chunk(ascii: &[u8; 16]) -> (v128, usize) {
+    let vectorized_ascii = u8x16_load(ascii);
+    let keep = u8x16_bitmask(u8x16_eq(invalid_lanes(vectorized_ascii), u8x16_splat(0)));
+
+    if keep == 0xFFFF {
+        return (vectorized_ascii, 16);
+    }
+
+    let (keep_lo, keep_hi) = (keep & 0xFF, keep >> 8);
+    let kept_lo = keep_lo.count_ones() as usize;
+
+    let mut indices = [0x80; 24];
+    indices[..8].copy_from_slice(&COMPACT_LUT[keep_lo as usize]);
+    for (index, &lane) in indices[kept_lo..kept_lo + 8]
+        .iter_mut()
+        .zip(&COMPACT_LUT[keep_hi as usize])
+    {
+        *index = lane | 8;
+    }
+
+    let indices = u8x16_load(
+        indices[..16]
+            .try_into()
+            .expect("Slice with incorrect length"),
+    );
+    let compacted = u8x16_swizzle(vectorized_ascii, indices);
+
+    (compacted, keep.count_ones() as usize)
+}
+
+/// [`validate_chunk`] runs only the character check of [`decode_chunk`].
+#[inline]
+pub(super) fn validate_chunk(ascii: &[u8; 16]) -> Result<(), DecodeError> {
+    match check_valid_characters(u8x16_load(ascii)) {
+        true => Ok(()),
+        false => Err(invalid_byte(ascii)),
+    }
+}
+
+/// [`invalid_byte`] finds the first lane that failed [`check_valid_characters`]. It is only
+/// reached on bad input, so it can be scalar.
+#[cold]
+fn invalid_byte(ascii: &[u8; 16]) -> DecodeError {
+    let index = ascii
+        .iter()
+        .position(|&byte| !check_valid_characters(u8x16_splat(byte)))
+        .unwrap_or_default();
+
+    DecodeError::InvalidByte(index, ascii[index])
+}
+
+#[inline]
+pub(super) fn decode_chunk(ascii: &[u8; 16]) -> Result<v128, DecodeError> {
     let vectorized_ascii = u8x16_load(ascii);
     let ascii_hashes = hash(vectorized_ascii);
     let sextets = sextets(vectorized_ascii, ascii_hashes);
 
     if !check_valid_characters(vectorized_ascii) {
-        return Err(String::from("invalid ascii characters"));
+        return Err(invalid_byte(ascii));
     }
 
     let low_sextets = u16x8_extend_low_u8x16(sextets);
@@ -180,4 +266,19 @@ mod tests {
             });
         }
     }
+
+    #[wasm_bindgen_test]
+    fn test_compact_chunk() {
+        let cases: [(&[u8; 16], &[u8]); 4] = [
+            (b"SGVsbG8gV29ybGQ=", b"SGVsbG8gV29ybGQ"),
+            (b"> SGVs\"bG8g\r\nV2\x00", b"SGVsbG8gV2"),
+            (b"================", b""),
+            (b"0123456789abcdef", b"0123456789abcdef"),
+        ];
+
+        for (ascii, expected) in cases {
+            let (compacted, count) = compact_chunk(ascii);
+            assert_eq!(&u8x16_to_array(compacted)[..count], expected);
+        }
+    }
 }
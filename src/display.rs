@@ -0,0 +1,94 @@
+//! Encode straight into a formatter, without allocating.
+
+use core::fmt;
+
+use crate::encode_chunk::encoded_len;
+use crate::encode_symbols;
+use crate::engine::{Engine, GeneralPurpose};
+
+/// Input bytes per block, a multiple of 3 so blocks never need padding in between.
+const BLOCK_LEN: usize = 768;
+
+/// [`Base64Display`] implements [`fmt::Display`] by encoding its bytes a block at a time into
+/// a stack buffer and writing each block to the formatter.
+#[derive(Debug, Clone, Copy)]
+pub struct Base64Display<'a> {
+    bytes: &'a [u8],
+    engine: GeneralPurpose,
+}
+
+impl<'a> Base64Display<'a> {
+    pub fn new(bytes: &'a [u8], engine: &GeneralPurpose) -> Self {
+        Self {
+            bytes,
+            engine: *engine,
+        }
+    }
+}
+
+impl fmt::Display for Base64Display<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = [0u8; BLOCK_LEN / 3 * 4];
+
+        for block in self.bytes.chunks(BLOCK_LEN) {
+            let written = encode_symbols(block, &mut buf, self.engine.alphabet());
+
+            // SAFETY: `encode_symbols` only writes ascii.
+            f.write_str(unsafe { core::str::from_utf8_unchecked(&buf[..written]) })?;
+        }
+
+        if self.engine.config().encode_padding() {
+            match encoded_len(self.bytes.len()) % 4 {
+                2 => f.write_str("==")?,
+                3 => f.write_str("=")?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+
+    #[wasm_bindgen_test]
+    fn test_display_matches_engine() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(BLOCK_LEN * 2 + 5).collect();
+
+        let lens = [
+            0,
+            1,
+            2,
+            3,
+            11,
+            BLOCK_LEN - 1,
+            BLOCK_LEN,
+            BLOCK_LEN + 1,
+            data.len(),
+        ];
+
+        for len in lens {
+            let bytes = &data[..len];
+
+            assert_eq!(
+                Base64Display::new(bytes, &STANDARD).to_string(),
+                STANDARD.encode(bytes)
+            );
+            assert_eq!(
+                Base64Display::new(bytes, &URL_SAFE_NO_PAD).to_string(),
+                URL_SAFE_NO_PAD.encode(bytes)
+            );
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_display_in_format() {
+        let header = format!("Basic {}", Base64Display::new(b"user:pass", &STANDARD));
+        assert_eq!(header, "Basic dXNlcjpwYXNz");
+    }
+}
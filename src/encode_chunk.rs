@@ -1,13 +1,13 @@
-use std::arch::wasm32::{
+use core::arch::wasm32::{
     i16x8_extend_high_u8x16, u16x8_add, u16x8_extend_low_u8x16, u16x8_shl, u8x16_ge, u8x16_shr,
     u8x16_shuffle, u8x16_splat, u8x16_sub, u8x16_sub_sat, u8x16_swizzle, v128, v128_and, v128_or,
 };
 
 use crate::impl_v128::{u16x8_to_array, u8x16_cycle, u8x16_load, u8x16_mask_splat};
 
-pub(super) fn encoded_len(input: usize) -> usize {
+pub(super) const fn encoded_len(input: usize) -> usize {
     let mod3 = input % 3;
-    input / 3 * 4 + (mod3 + (mod3 + 1) / 2)
+    input / 3 * 4 + (mod3 + mod3.div_ceil(2))
 }
 
 pub(super) fn encode_chunk(data: &[u8; 16]) -> v128 {
@@ -0,0 +1,401 @@
+//! An [`Engine`] API shaped like the one in the `base64` crate, so switching between the two
+//! is a matter of swapping the engine.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::alphabet::Alphabet;
+use crate::decode_chunk::decoded_len;
+use crate::{decode_symbols, encode_symbols, validate_symbols, DecodeError, EncodeError};
+
+/// [`DecodePaddingMode`] controls how trailing `=` padding is treated when decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodePaddingMode {
+    /// Accept either canonical padding or none at all.
+    Indifferent,
+    /// Require canonical padding.
+    RequireCanonical,
+    /// Reject any padding.
+    RequireNone,
+}
+
+/// [`GeneralPurposeConfig`] holds the knobs of a [`GeneralPurpose`] engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneralPurposeConfig {
+    encode_padding: bool,
+    decode_allow_trailing_bits: bool,
+    decode_padding_mode: DecodePaddingMode,
+}
+
+impl GeneralPurposeConfig {
+    /// [`GeneralPurposeConfig::new`] pads when encoding, requires canonical padding when
+    /// decoding and rejects nonzero trailing bits.
+    pub const fn new() -> Self {
+        Self {
+            encode_padding: true,
+            decode_allow_trailing_bits: false,
+            decode_padding_mode: DecodePaddingMode::RequireCanonical,
+        }
+    }
+
+    /// Whether to append `=` padding when encoding.
+    pub const fn with_encode_padding(self, padding: bool) -> Self {
+        Self {
+            encode_padding: padding,
+            ..self
+        }
+    }
+
+    /// Whether to accept a last symbol whose unused bits are not zero.
+    pub const fn with_decode_allow_trailing_bits(self, allow: bool) -> Self {
+        Self {
+            decode_allow_trailing_bits: allow,
+            ..self
+        }
+    }
+
+    /// How to treat padding when decoding.
+    pub const fn with_decode_padding_mode(self, mode: DecodePaddingMode) -> Self {
+        Self {
+            decode_padding_mode: mode,
+            ..self
+        }
+    }
+
+    /// Whether encoding appends `=` padding.
+    pub const fn encode_padding(&self) -> bool {
+        self.encode_padding
+    }
+}
+
+impl Default for GeneralPurposeConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// [`GeneralPurpose`] is the SIMD engine, parameterised by alphabet and config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneralPurpose {
+    alphabet: Alphabet,
+    config: GeneralPurposeConfig,
+}
+
+impl GeneralPurpose {
+    pub const fn new(alphabet: &Alphabet, config: GeneralPurposeConfig) -> Self {
+        Self {
+            alphabet: *alphabet,
+            config,
+        }
+    }
+}
+
+impl Engine for GeneralPurpose {
+    fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    fn config(&self) -> &GeneralPurposeConfig {
+        &self.config
+    }
+}
+
+/// Ready-made engines for the common alphabet and padding combinations.
+pub mod general_purpose {
+    use super::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+    use crate::alphabet;
+
+    /// Pads when encoding and requires canonical padding when decoding.
+    pub const PAD: GeneralPurposeConfig = GeneralPurposeConfig::new();
+
+    /// Never pads when encoding and rejects padding when decoding.
+    pub const NO_PAD: GeneralPurposeConfig = GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::RequireNone);
+
+    pub const STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, PAD);
+    pub const STANDARD_NO_PAD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, NO_PAD);
+    pub const URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, PAD);
+    pub const URL_SAFE_NO_PAD: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, NO_PAD);
+}
+
+/// [`Engine`] provides the `base64` crate's encode and decode methods on top of an alphabet
+/// and a [`GeneralPurposeConfig`].
+pub trait Engine {
+    fn alphabet(&self) -> Alphabet;
+
+    fn config(&self) -> &GeneralPurposeConfig;
+
+    /// [`Engine::encode`] encodes `input` into a new string.
+    #[cfg(feature = "alloc")]
+    fn encode<T: AsRef<[u8]>>(&self, input: T) -> String {
+        let mut output = String::new();
+        self.encode_string(input, &mut output);
+        output
+    }
+
+    /// [`Engine::encode_string`] appends the encoding of `input` to `output_buf`.
+    #[cfg(feature = "alloc")]
+    fn encode_string<T: AsRef<[u8]>>(&self, input: T, output_buf: &mut String) {
+        let input = input.as_ref();
+
+        // SAFETY: every byte written by `encode_slice` is ascii.
+        let buf = unsafe { output_buf.as_mut_vec() };
+        let start = buf.len();
+        let len = padded_len(input.len(), self.config())
+            .expect("usize overflow when calculating encoded length");
+        buf.resize(start + len, 0);
+
+        self.encode_slice(input, &mut buf[start..])
+            .expect("Buffer sized by padded_len");
+    }
+
+    /// [`Engine::encode_slice`] encodes `input` into the front of `output_buf` and returns
+    /// the number of bytes written.
+    fn encode_slice<T: AsRef<[u8]>>(
+        &self,
+        input: T,
+        output_buf: &mut [u8],
+    ) -> Result<usize, EncodeError> {
+        let input = input.as_ref();
+        let len = match padded_len(input.len(), self.config()) {
+            Some(len) if len <= output_buf.len() => len,
+            _ => return Err(EncodeError::OutputSliceTooSmall),
+        };
+
+        let written = encode_symbols(input, output_buf, self.alphabet());
+        output_buf[written..len].fill(b'=');
+
+        Ok(len)
+    }
+
+    /// [`Engine::decode`] decodes `input` into a new vector.
+    #[cfg(feature = "alloc")]
+    fn decode<T: AsRef<[u8]>>(&self, input: T) -> Result<Vec<u8>, DecodeError> {
+        let mut buffer = Vec::new();
+        self.decode_vec(input, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// [`Engine::decode_vec`] appends the decoding of `input` to `buffer`. On error
+    /// `buffer` is left as it was.
+    #[cfg(feature = "alloc")]
+    fn decode_vec<T: AsRef<[u8]>>(
+        &self,
+        input: T,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), DecodeError> {
+        let input = input.as_ref();
+
+        let start = buffer.len();
+        buffer.resize(start + decoded_len(input.len()), 0);
+
+        match self.decode_slice(input, &mut buffer[start..]) {
+            Ok(written) => {
+                buffer.truncate(start + written);
+                Ok(())
+            }
+            Err(err) => {
+                buffer.truncate(start);
+                Err(err)
+            }
+        }
+    }
+
+    /// [`Engine::decode_slice`] decodes `input` into the front of `output` and returns the
+    /// number of bytes written.
+    fn decode_slice<T: AsRef<[u8]>>(
+        &self,
+        input: T,
+        output: &mut [u8],
+    ) -> Result<usize, DecodeError> {
+        let symbols = strip_padding(input.as_ref(), self.config())?;
+
+        if !self.config().decode_allow_trailing_bits {
+            check_trailing_bits(symbols, self.alphabet())?;
+        }
+
+        if output.len() < decoded_len(symbols.len()) {
+            return Err(DecodeError::OutputSliceTooSmall);
+        }
+
+        decode_symbols(symbols, output, self.alphabet())
+    }
+
+    /// [`Engine::validate`] runs every check of [`Engine::decode_slice`] without writing
+    /// any decoded bytes.
+    fn validate<T: AsRef<[u8]>>(&self, input: T) -> Result<(), DecodeError> {
+        let symbols = strip_padding(input.as_ref(), self.config())?;
+
+        if !self.config().decode_allow_trailing_bits {
+            check_trailing_bits(symbols, self.alphabet())?;
+        }
+
+        validate_symbols(symbols, self.alphabet())
+    }
+}
+
+fn padded_len(input: usize, config: &GeneralPurposeConfig) -> Option<usize> {
+    crate::len::encoded_len(input, config.encode_padding)
+}
+
+/// [`strip_padding`] checks the trailing `=` against the padding mode and returns the
+/// symbols before it.
+fn strip_padding<'a>(
+    input: &'a [u8],
+    config: &GeneralPurposeConfig,
+) -> Result<&'a [u8], DecodeError> {
+    let padding = input.iter().rev().take_while(|&&b| b == b'=').count();
+    let symbols = &input[..input.len() - padding];
+
+    if symbols.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength(symbols.len()));
+    }
+
+    let canonical = (4 - symbols.len() % 4) % 4;
+    let valid = match (config.decode_padding_mode, padding) {
+        (DecodePaddingMode::Indifferent, 0) => true,
+        (DecodePaddingMode::Indifferent | DecodePaddingMode::RequireCanonical, n) => n == canonical,
+        (DecodePaddingMode::RequireNone, n) => n == 0,
+    };
+
+    match valid {
+        true => Ok(symbols),
+        false => Err(DecodeError::InvalidPadding),
+    }
+}
+
+/// [`check_trailing_bits`] rejects a last symbol whose bits fall outside the decoded bytes.
+pub(crate) fn check_trailing_bits(symbols: &[u8], alphabet: Alphabet) -> Result<(), DecodeError> {
+    let unused_mask = match symbols.len() % 4 {
+        2 => 0b1111,
+        3 => 0b11,
+        _ => return Ok(()),
+    };
+
+    let index = symbols.len() - 1;
+    match alphabet.sextet(symbols[index]) {
+        Some(sextet) if sextet & unused_mask != 0 => {
+            Err(DecodeError::InvalidLastSymbol(index, symbols[index]))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::base64::engine::general_purpose as theirs;
+    use ::base64::Engine as _;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_engines_match_base64_crate() {
+        let data: Vec<u8> = (0..=255u8).cycle().step_by(7).take(300).collect();
+        let engines = [
+            (STANDARD, theirs::STANDARD),
+            (STANDARD_NO_PAD, theirs::STANDARD_NO_PAD),
+            (URL_SAFE, theirs::URL_SAFE),
+            (URL_SAFE_NO_PAD, theirs::URL_SAFE_NO_PAD),
+        ];
+
+        for (ours, theirs) in engines {
+            for len in 0..data.len() {
+                let encoded = ours.encode(&data[..len]);
+                assert_eq!(encoded, theirs.encode(&data[..len]), "len {len}");
+                assert_eq!(ours.decode(&encoded), Ok(data[..len].to_vec()), "len {len}");
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_padding_modes() {
+        let indifferent = GeneralPurpose::new(
+            &crate::alphabet::STANDARD,
+            GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+        );
+
+        assert_eq!(
+            STANDARD.decode("SGVsbG8gV29ybGQ="),
+            Ok(b"Hello World".to_vec())
+        );
+        assert_eq!(
+            STANDARD.decode("SGVsbG8gV29ybGQ"),
+            Err(DecodeError::InvalidPadding)
+        );
+        assert_eq!(
+            STANDARD.decode("SGVsbG8gV29ybGQ=="),
+            Err(DecodeError::InvalidPadding)
+        );
+        assert_eq!(
+            STANDARD_NO_PAD.decode("SGVsbG8gV29ybGQ="),
+            Err(DecodeError::InvalidPadding)
+        );
+        assert_eq!(
+            STANDARD_NO_PAD.decode("SGVsbG8gV29ybGQ"),
+            Ok(b"Hello World".to_vec())
+        );
+        assert_eq!(
+            indifferent.decode("SGVsbG8gV29ybGQ="),
+            Ok(b"Hello World".to_vec())
+        );
+        assert_eq!(
+            indifferent.decode("SGVsbG8gV29ybGQ"),
+            Ok(b"Hello World".to_vec())
+        );
+        assert_eq!(STANDARD.decode("SGVsb"), Err(DecodeError::InvalidLength(5)));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_trailing_bits() {
+        let lenient = GeneralPurpose::new(
+            &crate::alphabet::STANDARD,
+            GeneralPurposeConfig::new().with_decode_allow_trailing_bits(true),
+        );
+
+        assert_eq!(
+            STANDARD.decode("SGVsbG8gV29ybGR="),
+            Err(DecodeError::InvalidLastSymbol(14, b'R'))
+        );
+        assert_eq!(
+            lenient.decode("SGVsbG8gV29ybGR="),
+            Ok(b"Hello World".to_vec())
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_url_safe_alphabet() {
+        assert_eq!(STANDARD.encode([0xfb, 0xff]), "+/8=");
+        assert_eq!(URL_SAFE_NO_PAD.encode([0xfb, 0xff]), "-_8");
+        assert_eq!(URL_SAFE_NO_PAD.decode("-_8"), Ok(vec![0xfb, 0xff]));
+        assert_eq!(
+            URL_SAFE_NO_PAD.decode("-_8+"),
+            Err(DecodeError::InvalidByte(3, b'+'))
+        );
+        assert_eq!(
+            STANDARD.decode("-_8="),
+            Err(DecodeError::InvalidByte(0, b'-'))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_encode_slice_and_decode_vec() {
+        let mut buf = [0u8; 16];
+        assert_eq!(STANDARD.encode_slice("Hello World", &mut buf), Ok(16));
+        assert_eq!(&buf, b"SGVsbG8gV29ybGQ=");
+        assert_eq!(
+            STANDARD.encode_slice("Hello World", &mut buf[..15]),
+            Err(EncodeError::OutputSliceTooSmall)
+        );
+
+        let mut out = b"> ".to_vec();
+        STANDARD.decode_vec("SGVsbG8gV29ybGQ=", &mut out).unwrap();
+        assert_eq!(out, b"> Hello World");
+        assert!(STANDARD.decode_vec("SGVsbG8*V29ybGQ=", &mut out).is_err());
+        assert_eq!(out, b"> Hello World");
+    }
+}
@@ -0,0 +1,97 @@
+use core::fmt;
+
+/// [`EncodeError`] describes why encoding could not complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The input was empty.
+    EmptyData,
+    /// The output slice cannot hold the encoded data.
+    OutputSliceTooSmall,
+    /// The encoded length does not fit in a `usize`.
+    LengthOverflow,
+    /// Lines cannot be wrapped at zero symbols.
+    InvalidLineLength,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyData => write!(f, "empty data"),
+            Self::OutputSliceTooSmall => write!(f, "output buffer too small"),
+            Self::LengthOverflow => write!(f, "encoded length overflows usize"),
+            Self::InvalidLineLength => write!(f, "zero line length"),
+        }
+    }
+}
+
+/// [`DecodeError`] describes why decoding could not complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A byte outside the alphabet was found at the given offset.
+    InvalidByte(usize, u8),
+    /// The number of symbols, padding excluded, can never be valid base64.
+    InvalidLength(usize),
+    /// The last symbol at the given offset has nonzero trailing bits.
+    InvalidLastSymbol(usize, u8),
+    /// The padding is missing, unexpected, or of the wrong length.
+    InvalidPadding,
+    /// Some symbol is outside the alphabet or has nonzero trailing bits. Which one is
+    /// withheld, see [`ct`](crate::ct).
+    InvalidSymbols,
+    /// The output slice cannot hold the decoded data.
+    OutputSliceTooSmall,
+}
+
+#[cfg(feature = "alloc")]
+impl DecodeError {
+    /// [`DecodeError::map_offset`] passes the offset the error carries, if any, through `f`.
+    pub(crate) fn map_offset(self, f: impl FnOnce(usize) -> usize) -> Self {
+        match self {
+            Self::InvalidByte(index, byte) => Self::InvalidByte(f(index), byte),
+            Self::InvalidLastSymbol(index, byte) => Self::InvalidLastSymbol(f(index), byte),
+            err => err,
+        }
+    }
+
+    /// [`DecodeError::shift`] moves the offset the error carries, if any, `by` bytes on.
+    pub(crate) fn shift(self, by: usize) -> Self {
+        self.map_offset(|index| by + index)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidByte(index, byte) => {
+                write!(f, "invalid byte {byte:#04x} at offset {index}")
+            }
+            Self::InvalidLength(len) => write!(f, "invalid input length {len}"),
+            Self::InvalidLastSymbol(index, byte) => {
+                write!(f, "invalid last symbol {byte:#04x} at offset {index}")
+            }
+            Self::InvalidPadding => write!(f, "invalid padding"),
+            Self::InvalidSymbols => write!(f, "invalid symbols"),
+            Self::OutputSliceTooSmall => write!(f, "output buffer too small"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+#[cfg(feature = "alloc")]
+impl From<EncodeError> for wasm_bindgen::JsValue {
+    fn from(err: EncodeError) -> Self {
+        Self::from_str(&alloc::string::ToString::to_string(&err))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<DecodeError> for wasm_bindgen::JsValue {
+    fn from(err: DecodeError) -> Self {
+        Self::from_str(&alloc::string::ToString::to_string(&err))
+    }
+}
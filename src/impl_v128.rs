@@ -1,4 +1,4 @@
-use std::arch::wasm32::{v128, v128_bitselect, v128_load};
+use core::arch::wasm32::{v128, v128_bitselect, v128_load};
 
 use paste::paste;
 
@@ -19,7 +19,7 @@ macro_rules! impl_v128 {
             pub fn [< $ty x $lane_count  _to_array>](val: v128) -> [$ty; $lane_count] {
                 let mut buf = [0 as $ty; $lane_count];
                 unsafe {
-                    std::ptr::copy_nonoverlapping(&val as *const v128 as *const $ty, buf.as_mut_ptr(), $lane_count);
+                    core::ptr::copy_nonoverlapping(&val as *const v128 as *const $ty, buf.as_mut_ptr(), $lane_count);
                 }
                 buf
             }
@@ -52,7 +52,7 @@ macro_rules! impl_v128 {
                 [<$ty x $lane_count _load>](&out)
             }
 
-            use std::arch::wasm32::[<$ty x $lane_count _splat>];
+            use core::arch::wasm32::[<$ty x $lane_count _splat>];
 
             /// `[<$ty x $lane_count _mask_splat>]` is based on the bitmask,
             ///       where true => use `select_if_true`
@@ -75,7 +75,7 @@ impl_v128!(i16, 8);
 
 #[cfg(test)]
 mod tests {
-    use std::arch::wasm32::{i16x8_splat, u8x16_splat, v128_not};
+    use core::arch::wasm32::{i16x8_splat, u8x16_splat, v128_not};
 
     use wasm_bindgen_test::wasm_bindgen_test;
 
@@ -0,0 +1,114 @@
+//! Encode to and decode from JS strings directly, skipping the UTF-8 transcoding that
+//! wasm-bindgen does for `String`. Base64 is ascii, so one code unit is one byte.
+
+use js_sys::{JsString, Uint8Array};
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+
+use crate::alphabet::Alphabet;
+use crate::buffer_source::byte_view;
+use crate::{decode_in_place, encode_symbols};
+
+/// Input bytes per `String.fromCharCode` call, a multiple of 3 so blocks never need padding in
+/// between, and few enough code units to stay under engines' argument limits.
+const BLOCK_LEN: usize = 6144;
+
+#[wasm_bindgen(inline_js = "
+export function write_code_units(string, view) {
+    for (let i = 0; i < string.length; i++) {
+        const unit = string.charCodeAt(i);
+        view[i] = unit > 0xff ? 0xff : unit;
+    }
+}
+")]
+extern "C" {
+    /// Writes each code unit of `string` into `view`, saturating at `0xff` so no unit
+    /// outside Latin-1 can pass for a symbol.
+    fn write_code_units(string: &JsString, view: &Uint8Array);
+}
+
+/// [`encode_to_js_string_js`] encodes any `BufferSource` straight into a JS string.
+#[wasm_bindgen(js_name = encode_to_js_string)]
+pub fn encode_to_js_string_js(data: &JsValue) -> Result<JsString, JsValue> {
+    Ok(encode_to_js_string(&byte_view(data)?.to_vec()))
+}
+
+/// [`encode_to_js_string`] encodes bytes straight into a JS string. Each block is encoded on
+/// the stack, widened to code units and handed to `String.fromCharCode`.
+pub fn encode_to_js_string(data: &[u8]) -> JsString {
+    let mut symbols = [0u8; BLOCK_LEN / 3 * 4];
+    let mut units = [0u16; BLOCK_LEN / 3 * 4];
+    let mut ascii = JsString::from("");
+
+    for block in data.chunks(BLOCK_LEN) {
+        let written = encode_symbols(block, &mut symbols, Alphabet::Standard);
+        widen(&symbols[..written], &mut units[..written]);
+
+        ascii = ascii.concat(&JsString::from_char_code(&units[..written]));
+    }
+
+    match data.len() % 3 {
+        1 => ascii.concat(&"==".into()),
+        2 => ascii.concat(&"=".into()),
+        _ => ascii,
+    }
+}
+
+/// [`decode_from_js_string`] decodes a JS string by copying its code units into wasm memory
+/// as bytes, then decoding them in place.
+#[wasm_bindgen]
+pub fn decode_from_js_string(ascii: &JsString) -> Result<Vec<u8>, JsValue> {
+    let mut data = vec![0u8; ascii.length() as usize];
+
+    // SAFETY: the view covers exactly `data`, is only written by `write_code_units`, which
+    // does not allocate in wasm memory, and is dropped before `data` is touched again.
+    let view = unsafe { Uint8Array::view_mut_raw(data.as_mut_ptr(), data.len()) };
+    write_code_units(ascii, &view);
+    drop(view);
+
+    let len = decode_in_place(&mut data)?.len();
+    data.truncate(len);
+    Ok(data)
+}
+
+fn widen(symbols: &[u8], units: &mut [u16]) {
+    for (unit, &symbol) in units.iter_mut().zip(symbols) {
+        *unit = symbol as u16;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::engine::general_purpose::STANDARD;
+    use crate::engine::Engine;
+
+    #[wasm_bindgen_test]
+    fn test_widen() {
+        let mut units = [0u16; 4];
+        widen(b"Zm8=", &mut units);
+        assert_eq!(units, [0x5A, 0x6D, 0x38, 0x3D]);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_round_trip() -> Result<(), JsValue> {
+        let data: Vec<u8> = (0..=255u8).cycle().take(2 * BLOCK_LEN + 50).collect();
+
+        for len in [0, 1, 2, 15, 16, 17, 100, BLOCK_LEN + 1, data.len()] {
+            let ascii = encode_to_js_string(&data[..len]);
+            assert_eq!(String::from(&ascii), STANDARD.encode(&data[..len]));
+            assert_eq!(decode_from_js_string(&ascii)?, &data[..len]);
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rejects_code_units_above_latin1() {
+        // U+0141 would read as 'A' if truncated to its low byte.
+        assert!(decode_from_js_string(&JsString::from("SGVs\u{141}G8=")).is_err());
+        assert!(decode_from_js_string(&JsString::from("SGVs\u{100}G8=")).is_err());
+        assert!(decode_from_js_string(&JsString::from("SGVsbG8=")).is_ok());
+    }
+}
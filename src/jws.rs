@@ -0,0 +1,324 @@
+//! Split and build JWS compact serializations, `header.payload.signature` with each segment
+//! in unpadded URL-safe base64 ([RFC 7515 §7.1]). JWTs are JWSs with a JSON payload.
+//!
+//! Segments are decoded strictly: padding and nonzero trailing bits are rejected, so every
+//! token has exactly one spelling. Signatures are returned, not verified.
+//!
+//! [RFC 7515 §7.1]: https://www.rfc-editor.org/rfc/rfc7515#section-7.1
+
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use js_sys::{Object, Reflect, Uint8Array, JSON};
+#[cfg(feature = "std")]
+use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "std")]
+use wasm_bindgen::JsValue;
+
+#[cfg(feature = "std")]
+use crate::buffer_source::byte_view;
+use crate::engine::general_purpose::URL_SAFE_NO_PAD;
+use crate::engine::Engine;
+use crate::DecodeError;
+
+/// [`Jws`] holds the decoded segments of a compact token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jws {
+    /// The JOSE header, as JSON bytes.
+    pub header: Vec<u8>,
+    /// The payload, as JSON bytes for a JWT.
+    pub payload: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+/// [`Segment`] names one of the three parts of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment {
+    Header,
+    Payload,
+    Signature,
+}
+
+/// [`JwsError`] describes why a token could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwsError {
+    /// The token does not have three `.`-separated segments, it has the given number.
+    SegmentCount(usize),
+    /// A segment is not strict unpadded URL-safe base64. Offsets index into the token.
+    InvalidSegment(Segment, DecodeError),
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header => write!(f, "header"),
+            Self::Payload => write!(f, "payload"),
+            Self::Signature => write!(f, "signature"),
+        }
+    }
+}
+
+impl fmt::Display for JwsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SegmentCount(count) => write!(f, "expected 3 segments, found {count}"),
+            Self::InvalidSegment(segment, err) => write!(f, "invalid {segment}: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for JwsError {}
+
+#[cfg(feature = "std")]
+impl From<JwsError> for JsValue {
+    fn from(err: JwsError) -> Self {
+        Self::from_str(&err.to_string())
+    }
+}
+
+/// [`decode_jws_js`] decodes a compact token into `{ header, payload, signature }`, with the
+/// header parsed as JSON. The payload is parsed as JSON too, or left as a `Uint8Array` when it
+/// is not JSON, such as a detached or binary payload.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = decode_jws)]
+pub fn decode_jws_js(token: &str) -> Result<Object, JsValue> {
+    let Jws {
+        header,
+        payload,
+        signature,
+    } = decode(token)?;
+
+    let header = String::from_utf8(header).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let payload = match core::str::from_utf8(&payload).map(JSON::parse) {
+        Ok(Ok(json)) => json,
+        _ => Uint8Array::from(payload.as_slice()).into(),
+    };
+
+    let jws = Object::new();
+    Reflect::set(&jws, &"header".into(), &JSON::parse(&header)?)?;
+    Reflect::set(&jws, &"payload".into(), &payload)?;
+    Reflect::set(
+        &jws,
+        &"signature".into(),
+        &Uint8Array::from(signature.as_slice()),
+    )?;
+
+    Ok(jws)
+}
+
+/// [`encode_jws_js`] builds a compact token from a header serialized with `JSON.stringify`,
+/// a payload taken as is from a `BufferSource` or otherwise serialized the same way, and the
+/// signature bytes from any `BufferSource`.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = encode_jws)]
+pub fn encode_jws_js(
+    header: &JsValue,
+    payload: &JsValue,
+    signature: &JsValue,
+) -> Result<String, JsValue> {
+    let header = String::from(JSON::stringify(header)?);
+    let payload = match byte_view(payload) {
+        Ok(view) => view.to_vec(),
+        Err(_) => String::from(JSON::stringify(payload)?).into_bytes(),
+    };
+    let signature = byte_view(signature)?.to_vec();
+
+    Ok(encode(header.as_bytes(), &payload, &signature))
+}
+
+/// [`decode`] splits `token` on `.` and decodes each segment.
+pub fn decode(token: &str) -> Result<Jws, JwsError> {
+    let mut segments = token.split('.');
+
+    let (Some(header), Some(payload), Some(signature), None) = (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) else {
+        return Err(JwsError::SegmentCount(token.split('.').count()));
+    };
+
+    let payload_start = header.len() + 1;
+    let signature_start = payload_start + payload.len() + 1;
+
+    Ok(Jws {
+        header: decode_segment(header, Segment::Header, 0)?,
+        payload: decode_segment(payload, Segment::Payload, payload_start)?,
+        signature: decode_segment(signature, Segment::Signature, signature_start)?,
+    })
+}
+
+/// [`encode`] builds the compact serialization of the given segments.
+pub fn encode(header: &[u8], payload: &[u8], signature: &[u8]) -> String {
+    let mut token = signing_input(header, payload);
+    token.push('.');
+    URL_SAFE_NO_PAD.encode_string(signature, &mut token);
+    token
+}
+
+/// [`signing_input`] returns `header.payload`, the bytes a JWS signature covers.
+pub fn signing_input(header: &[u8], payload: &[u8]) -> String {
+    let mut input = String::new();
+    URL_SAFE_NO_PAD.encode_string(header, &mut input);
+    input.push('.');
+    URL_SAFE_NO_PAD.encode_string(payload, &mut input);
+    input
+}
+
+fn decode_segment(segment: &str, name: Segment, offset: usize) -> Result<Vec<u8>, JwsError> {
+    URL_SAFE_NO_PAD
+        .decode(segment)
+        .map_err(|err| JwsError::InvalidSegment(name, err.shift(offset)))
+}
+
+#[cfg(feature = "serde")]
+impl Jws {
+    /// [`Jws::header_json`] deserializes the header.
+    pub fn header_json<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.header)
+    }
+
+    /// [`Jws::payload_json`] deserializes the payload, such as a JWT's claims.
+    pub fn payload_json<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.payload)
+    }
+}
+
+/// [`encode_json`] builds a compact token from a header and payload serialized to JSON.
+#[cfg(feature = "serde")]
+pub fn encode_json<H, P>(header: &H, payload: &P, signature: &[u8]) -> serde_json::Result<String>
+where
+    H: serde::Serialize + ?Sized,
+    P: serde::Serialize + ?Sized,
+{
+    let header = serde_json::to_vec(header)?;
+    let payload = serde_json::to_vec(payload)?;
+
+    Ok(encode(&header, &payload, signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    /// The HS256 example of RFC 7515 Appendix A.1.
+    const TOKEN: &str = "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9\
+        .eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ\
+        .dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    #[wasm_bindgen_test]
+    fn test_rfc7515_example() -> Result<(), JwsError> {
+        let jws = decode(TOKEN)?;
+
+        assert_eq!(jws.header, b"{\"typ\":\"JWT\",\r\n \"alg\":\"HS256\"}");
+        assert_eq!(
+            jws.payload,
+            b"{\"iss\":\"joe\",\r\n \"exp\":1300819380,\r\n \"http://example.com/is_root\":true}"
+        );
+        assert_eq!(jws.signature.len(), 32);
+        assert_eq!(jws.signature[..4], [116, 24, 223, 180]);
+
+        assert_eq!(encode(&jws.header, &jws.payload, &jws.signature), TOKEN);
+        assert_eq!(
+            signing_input(&jws.header, &jws.payload),
+            TOKEN[..TOKEN.rfind('.').unwrap()]
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_unsecured() -> Result<(), JwsError> {
+        let jws = decode("eyJhbGciOiJub25lIn0.e30.")?;
+
+        assert_eq!(jws.header, b"{\"alg\":\"none\"}");
+        assert_eq!(jws.payload, b"{}");
+        assert!(jws.signature.is_empty());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_strict() {
+        assert_eq!(decode("e30"), Err(JwsError::SegmentCount(1)));
+        assert_eq!(decode("e30.e30.e30.e30"), Err(JwsError::SegmentCount(4)));
+        assert_eq!(
+            decode("e30=.e30.AA"),
+            Err(JwsError::InvalidSegment(
+                Segment::Header,
+                DecodeError::InvalidPadding
+            ))
+        );
+        assert_eq!(
+            decode("e30.e31.AA"),
+            Err(JwsError::InvalidSegment(
+                Segment::Payload,
+                DecodeError::InvalidLastSymbol(6, b'1')
+            ))
+        );
+        assert_eq!(
+            decode("e30.e30.A+"),
+            Err(JwsError::InvalidSegment(
+                Segment::Signature,
+                DecodeError::InvalidByte(9, b'+')
+            ))
+        );
+        assert_eq!(
+            decode("e30.e30.A"),
+            Err(JwsError::InvalidSegment(
+                Segment::Signature,
+                DecodeError::InvalidLength(1)
+            ))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[wasm_bindgen_test]
+    fn test_js_payloads() -> Result<(), JsValue> {
+        use wasm_bindgen::JsCast;
+
+        let payload = |jws: &Object| Reflect::get(jws, &"payload".into());
+
+        let jws = decode_jws_js(TOKEN)?;
+        assert_eq!(Reflect::get(&payload(&jws)?, &"iss".into())?, "joe");
+
+        let header = JSON::parse("{\"alg\":\"none\"}")?;
+        let binary = Uint8Array::from(&[0xff, 0, b'{'][..]);
+        let token = encode_jws_js(&header, &binary, &Uint8Array::new_with_length(0))?;
+        assert_eq!(token, encode(b"{\"alg\":\"none\"}", &[0xff, 0, b'{'], &[]));
+
+        let jws = decode_jws_js(&token)?;
+        let bytes: Uint8Array = payload(&jws)?.dyn_into()?;
+        assert_eq!(bytes.to_vec(), [0xff, 0, b'{']);
+
+        let detached = decode_jws_js("eyJhbGciOiJub25lIn0..")?;
+        let bytes: Uint8Array = payload(&detached)?.dyn_into()?;
+        assert_eq!(bytes.length(), 0);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[wasm_bindgen_test]
+    fn test_serde() -> serde_json::Result<()> {
+        let jws = decode(TOKEN).unwrap();
+        let header: serde_json::Value = jws.header_json()?;
+        let claims: serde_json::Value = jws.payload_json()?;
+        assert_eq!(header["alg"], "HS256");
+        assert_eq!(claims["exp"], 1300819380);
+
+        let token = encode_json(&header, &claims, &[1, 2, 3])?;
+        assert_eq!(
+            decode(&token)
+                .unwrap()
+                .payload_json::<serde_json::Value>()?,
+            claims
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+//! Overflow-checked output length calculators, for sizing buffers ahead of a call.
+
+#[cfg(feature = "alloc")]
+use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "std")]
+use wasm_bindgen::JsValue;
+
+use crate::DecodeError;
+
+/// [`LineEnding`] separates the lines of wrapped output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    pub const fn as_bytes(self) -> &'static [u8] {
+        self.as_str().as_bytes()
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+/// [`encoded_len`] returns the length `bytes_len` bytes encode to, or `None` if it does not
+/// fit in a `usize`.
+pub const fn encoded_len(bytes_len: usize, padding: bool) -> Option<usize> {
+    let Some(full) = (bytes_len / 3).checked_mul(4) else {
+        return None;
+    };
+
+    let tail = match (bytes_len % 3, padding) {
+        (0, _) => 0,
+        (_, true) => 4,
+        (1, false) => 2,
+        (_, false) => 3,
+    };
+
+    full.checked_add(tail)
+}
+
+/// [`wrapped_encoded_len`] returns the length `bytes_len` bytes encode to when split into
+/// lines of `line_len` symbols, with `line_ending` between lines but not after the last.
+/// Returns `None` if it does not fit in a `usize` or `line_len` is zero.
+pub const fn wrapped_encoded_len(
+    bytes_len: usize,
+    padding: bool,
+    line_len: usize,
+    line_ending: LineEnding,
+) -> Option<usize> {
+    let Some(len) = encoded_len(bytes_len, padding) else {
+        return None;
+    };
+
+    if line_len == 0 {
+        return None;
+    }
+
+    let line_breaks = len.div_ceil(line_len).saturating_sub(1);
+    let Some(line_endings_len) = line_breaks.checked_mul(line_ending.as_bytes().len()) else {
+        return None;
+    };
+
+    len.checked_add(line_endings_len)
+}
+
+/// [`decoded_len_estimate`] returns an upper bound on the bytes any input of `encoded_len`
+/// bytes decodes to, whether padded, unpadded or wrapped. It never overflows.
+pub const fn decoded_len_estimate(encoded_len: usize) -> usize {
+    crate::decode_chunk::decoded_len(encoded_len)
+}
+
+/// [`decoded_len`] returns exactly how many bytes unwrapped `ascii` decodes to, looking past
+/// any trailing padding. It does not check the symbols themselves. Use
+/// [`wrapped_decoded_len`] for input split into lines.
+pub const fn decoded_len(ascii: &[u8]) -> Result<usize, DecodeError> {
+    let mut symbols = ascii.len();
+    while symbols > 0 && ascii[symbols - 1] == b'=' {
+        symbols -= 1;
+    }
+
+    symbols_decoded_len(symbols)
+}
+
+/// [`wrapped_decoded_len`] is [`decoded_len`] for input with line breaks or other ascii
+/// whitespace anywhere in it, as `decode_wrapped_to` accepts.
+pub const fn wrapped_decoded_len(ascii: &[u8]) -> Result<usize, DecodeError> {
+    let mut symbols = 0;
+    let mut padding = 0;
+
+    let mut i = 0;
+    while i < ascii.len() {
+        match ascii[i] {
+            b'=' => padding += 1,
+            byte if byte.is_ascii_whitespace() => {}
+            _ => {
+                symbols += padding + 1;
+                padding = 0;
+            }
+        }
+        i += 1;
+    }
+
+    symbols_decoded_len(symbols)
+}
+
+const fn symbols_decoded_len(symbols: usize) -> Result<usize, DecodeError> {
+    match symbols % 4 {
+        1 => Err(DecodeError::InvalidLength(symbols)),
+        _ => Ok(crate::decode_chunk::decoded_len(symbols)),
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[wasm_bindgen(js_name = encoded_len)]
+pub fn encoded_len_js(bytes_len: usize, padding: bool) -> Option<usize> {
+    encoded_len(bytes_len, padding)
+}
+
+#[cfg(feature = "alloc")]
+#[wasm_bindgen(js_name = wrapped_encoded_len)]
+pub fn wrapped_encoded_len_js(
+    bytes_len: usize,
+    padding: bool,
+    line_len: usize,
+    crlf: bool,
+) -> Option<usize> {
+    let line_ending = match crlf {
+        true => LineEnding::CrLf,
+        false => LineEnding::Lf,
+    };
+
+    wrapped_encoded_len(bytes_len, padding, line_len, line_ending)
+}
+
+#[cfg(feature = "alloc")]
+#[wasm_bindgen(js_name = decoded_len_estimate)]
+pub fn decoded_len_estimate_js(encoded_len: usize) -> usize {
+    decoded_len_estimate(encoded_len)
+}
+
+/// [`decoded_len_js`] reads only the length of `ascii`, any `BufferSource`, and its trailing
+/// padding, so the bytes are not copied into wasm memory.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = decoded_len)]
+pub fn decoded_len_js(ascii: &JsValue) -> Result<usize, JsValue> {
+    let ascii = crate::buffer_source::byte_view(ascii)?;
+
+    let mut symbols = ascii.length();
+    while symbols > 0 && ascii.get_index(symbols - 1) == b'=' {
+        symbols -= 1;
+    }
+
+    Ok(symbols_decoded_len(symbols as usize)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+    use crate::engine::Engine;
+
+    #[wasm_bindgen_test]
+    fn test_lengths_match_engine() {
+        let data = [0u8; 100];
+
+        for len in 0..data.len() {
+            let padded = STANDARD.encode(&data[..len]);
+            let unpadded = STANDARD_NO_PAD.encode(&data[..len]);
+
+            assert_eq!(encoded_len(len, true), Some(padded.len()));
+            assert_eq!(encoded_len(len, false), Some(unpadded.len()));
+            assert_eq!(decoded_len(padded.as_bytes()), Ok(len));
+            assert_eq!(decoded_len(unpadded.as_bytes()), Ok(len));
+            assert!(decoded_len_estimate(padded.len()) >= len);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_overflow() {
+        assert_eq!(encoded_len(usize::MAX, true), None);
+        assert_eq!(
+            encoded_len(usize::MAX / 4 * 3, true),
+            Some(usize::MAX / 4 * 4)
+        );
+        assert_eq!(
+            wrapped_encoded_len(usize::MAX / 4 * 3, true, 64, LineEnding::Lf),
+            None
+        );
+        assert_eq!(decoded_len_estimate(usize::MAX), usize::MAX / 4 * 3 + 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_wrapped_encoded_len() {
+        assert_eq!(wrapped_encoded_len(0, true, 64, LineEnding::Lf), Some(0));
+        assert_eq!(wrapped_encoded_len(48, true, 64, LineEnding::Lf), Some(64));
+        assert_eq!(wrapped_encoded_len(49, true, 64, LineEnding::Lf), Some(69));
+        assert_eq!(
+            wrapped_encoded_len(49, true, 64, LineEnding::CrLf),
+            Some(70)
+        );
+        assert_eq!(
+            wrapped_encoded_len(49, false, 64, LineEnding::CrLf),
+            Some(68)
+        );
+        assert_eq!(wrapped_encoded_len(49, true, 0, LineEnding::Lf), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[wasm_bindgen_test]
+    fn test_wrapped_decoded_len() {
+        let data = [0u8; 100];
+
+        for len in 0..data.len() {
+            for line_ending in [LineEnding::Lf, LineEnding::CrLf] {
+                let mut ascii = alloc::vec::Vec::new();
+                crate::encode_wrapped_to(&data[..len], 16, line_ending, &mut ascii)
+                    .expect("nonzero line length");
+                ascii.extend_from_slice(line_ending.as_bytes());
+
+                assert_eq!(wrapped_decoded_len(&ascii), Ok(len));
+            }
+        }
+
+        assert_eq!(wrapped_decoded_len(b" SGVs\r\nbG8=\n\t"), Ok(5));
+        assert_eq!(
+            wrapped_decoded_len(b"SGVs\nb\n==\n"),
+            Err(DecodeError::InvalidLength(5))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decoded_len_rejects_impossible_length() {
+        assert_eq!(decoded_len(b"SGVsb"), Err(DecodeError::InvalidLength(5)));
+        assert_eq!(decoded_len(b"SGVsb==="), Err(DecodeError::InvalidLength(5)));
+    }
+}
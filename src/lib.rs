@@ -1,45 +1,106 @@
 #![cfg(target_arch = "wasm32")]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod alphabet;
+#[cfg(feature = "alloc")]
+pub mod armor;
+#[cfg(feature = "std")]
 mod base64;
+#[cfg(feature = "alloc")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod blob;
+#[cfg(feature = "std")]
+pub mod buffer;
+#[cfg(feature = "std")]
+mod buffer_source;
+pub mod ct;
+#[cfg(feature = "alloc")]
+pub mod data_url;
 mod decode_chunk;
+pub mod display;
 mod encode_chunk;
+pub mod engine;
+mod error;
 mod fuzz;
 pub mod impl_v128;
+#[cfg(feature = "std")]
+pub mod js_string;
+#[cfg(feature = "alloc")]
+pub mod jws;
+pub mod len;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+#[cfg(feature = "alloc")]
+pub mod pem;
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod progress;
+pub mod scalar;
+#[cfg(feature = "alloc")]
+pub mod ssh;
+#[cfg(feature = "alloc")]
+pub mod typed;
 
-use std::arch::wasm32::v128;
-use std::slice;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::arch::wasm32::v128;
+#[cfg(feature = "alloc")]
+use core::slice;
 
-use decode_chunk::{decode_chunk, decoded_len};
+use alphabet::Alphabet;
+use decode_chunk::{compact_chunk, decode_chunk, decoded_len, validate_chunk};
 use encode_chunk::{encode_chunk, encoded_len};
+use engine::Engine;
+pub use error::{DecodeError, EncodeError};
+use impl_v128::u8x16_to_array;
+#[cfg(feature = "alloc")]
 use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "alloc")]
 use wasm_bindgen::JsValue;
 
 /// [`atob`] decodes a string of data from an ascii string.
+#[cfg(feature = "alloc")]
 #[wasm_bindgen]
 pub fn atob(ascii: String) -> Result<String, JsValue> {
     Ok(unsafe { String::from_utf8_unchecked(decode(ascii.as_bytes())?)})
 }
 
 /// [`btoa`] encodes a string of data to an ascii string.
+#[cfg(feature = "alloc")]
 #[wasm_bindgen]
 pub fn btoa(binary_string: String) -> Result<String, JsValue> {
     encode_to_utf8(binary_string.as_bytes())
 }
 
 /// [`encode`] converts bytes into a base64-encoded byte array.
-#[wasm_bindgen]
+#[cfg(feature = "alloc")]
+#[cfg_attr(not(feature = "std"), wasm_bindgen)]
 pub fn encode(data: &[u8]) -> Result<Vec<u8>, JsValue> {
     let mut ascii = Vec::new();
-    encode_to(data, &mut ascii).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    encode_to(data, &mut ascii)?;
     Ok(ascii)
 }
 
-#[wasm_bindgen]
+/// [`encode_to_string`] appends the base64 encoding of `data` to `out`.
+#[cfg(feature = "alloc")]
+pub fn encode_to_string(data: &[u8], out: &mut String) {
+    engine::general_purpose::STANDARD.encode_string(data, out);
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(not(feature = "std"), wasm_bindgen)]
 pub fn encode_to_utf8(data: &[u8]) -> Result<String, JsValue> {
     Ok(unsafe { String::from_utf8_unchecked(encode(data)?) })
 }
 
 /// [`decode`] takes ascii and returns its original binary representation.
+#[cfg(feature = "alloc")]
 #[wasm_bindgen]
 pub fn decode(ascii: &[u8]) -> Result<Vec<u8>, JsValue> {
     let mut data = Vec::new();
@@ -47,12 +108,114 @@ pub fn decode(ascii: &[u8]) -> Result<Vec<u8>, JsValue> {
     Ok(data)
 }
 
-fn encode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+/// [`encode_vectored_js`] encodes an array of `BufferSource`s as one stream.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = encode_vectored)]
+pub fn encode_vectored_js(parts: js_sys::Array) -> Result<Vec<u8>, JsValue> {
+    let parts = parts
+        .iter()
+        .map(|part| Ok(buffer_source::byte_view(&part)?.to_vec()))
+        .collect::<Result<Vec<_>, JsValue>>()?;
+
+    let mut ascii = Vec::new();
+    encode_slices_to(parts.iter().map(Vec::as_slice), &mut ascii);
+    Ok(ascii)
+}
+
+/// [`encode_vectored`] encodes `bufs` as one stream, as if they were concatenated.
+#[cfg(feature = "std")]
+pub fn encode_vectored(bufs: &[std::io::IoSlice<'_>]) -> Vec<u8> {
+    let mut ascii = Vec::new();
+    encode_slices_to(bufs.iter().map(|buf| &**buf), &mut ascii);
+    ascii
+}
+
+/// [`encode_slices_to`] appends the padded encoding of `slices`, concatenated, to `out`.
+#[cfg(feature = "std")]
+fn encode_slices_to<'a>(slices: impl Iterator<Item = &'a [u8]> + Clone, out: &mut Vec<u8>) {
+    let total = slices
+        .clone()
+        .try_fold(0usize, |total, slice| total.checked_add(slice.len()))
+        .and_then(|total| len::encoded_len(total, true))
+        .expect("usize overflow when calculating encoded length");
+    out.reserve(total);
+
+    let mut encoder = StreamEncoder::new(out);
+    slices.for_each(|slice| encoder.push(slice));
+    encoder.finish();
+}
+
+/// [`StreamEncoder`] appends the encoding of bytes that arrive in arbitrary pieces to `out`,
+/// carrying up to two bytes from one piece into the next so only the final group is padded.
+#[cfg(feature = "std")]
+pub(crate) struct StreamEncoder<'a> {
+    out: &'a mut Vec<u8>,
+    carry: [u8; 3],
+    carried: usize,
+}
+
+#[cfg(feature = "std")]
+impl<'a> StreamEncoder<'a> {
+    pub(crate) fn new(out: &'a mut Vec<u8>) -> Self {
+        Self {
+            out,
+            carry: [0; 3],
+            carried: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, mut data: &[u8]) {
+        if self.carried > 0 {
+            let take = (3 - self.carried).min(data.len());
+            self.carry[self.carried..self.carried + take].copy_from_slice(&data[..take]);
+            self.carried += take;
+            data = &data[take..];
+
+            if self.carried < 3 {
+                return;
+            }
+
+            let group = self.carry;
+            self.append(&group);
+        }
+
+        let whole = data.len() / 3 * 3;
+        self.append(&data[..whole]);
+
+        self.carried = data.len() - whole;
+        self.carry[..self.carried].copy_from_slice(&data[whole..]);
+    }
+
+    /// [`StreamEncoder::finish`] encodes the carried bytes and pads the final group.
+    pub(crate) fn finish(mut self) {
+        let rest = self.carry;
+        self.append(&rest[..self.carried]);
+
+        match self.carried {
+            1 => self.out.extend_from_slice(b"=="),
+            2 => self.out.extend_from_slice(b"="),
+            _ => {}
+        }
+    }
+
+    fn append(&mut self, data: &[u8]) {
+        let start = self.out.len();
+        self.out.resize(start + data.len().div_ceil(3) * 4, 0);
+
+        let written = encode_symbols(data, &mut self.out[start..], Alphabet::Standard);
+        self.out.truncate(start + written);
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn encode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), EncodeError> {
     if data.is_empty() {
-        return Err(String::from("empty data"));
+        return Err(EncodeError::EmptyData);
     }
 
-    out.reserve(encoded_len(data.len()) + 16);
+    let len =
+        len::encoded_len(data.len(), true).expect("usize overflow when calculating encoded length");
+    out.reserve(len + 16);
     let mut raw_out = out.as_mut_ptr_range().end;
 
     let mut start = data.as_ptr();
@@ -113,7 +276,8 @@ fn encode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
     Ok(())
 }
 
-pub fn decode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+#[cfg(feature = "alloc")]
+pub fn decode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), DecodeError> {
     let data = match data {
         [p @ .., b'=', b'='] | [p @ .., b'='] | p => p,
     };
@@ -126,13 +290,10 @@ pub fn decode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
     let mut raw_out = out.as_mut_ptr_range().end;
 
     let mut chunks = data.chunks_exact(16);
-    let mut failed = false;
 
-    for chunk in &mut chunks {
+    for (i, chunk) in (&mut chunks).enumerate() {
         let ascii = chunk.try_into().expect("Slice with incorrect length");
-        let decoded = decode_chunk(ascii);
-        failed |= decoded.is_err();
-        let decoded = decoded.unwrap();
+        let decoded = decode_chunk(ascii).map_err(|e| rebase(e, data, i * 16))?;
 
         unsafe {
             raw_out.cast::<v128>().write_unaligned(decoded);
@@ -144,9 +305,7 @@ pub fn decode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
     if !rest.is_empty() {
         let mut ascii = [b'A'; 16];
         ascii[0..rest.len()].copy_from_slice(rest);
-        let decoded = decode_chunk(&ascii);
-        failed |= decoded.is_err();
-        let decoded = decoded.unwrap();
+        let decoded = decode_chunk(&ascii).map_err(|e| rebase(e, data, data.len() - rest.len()))?;
 
         unsafe {
             raw_out.cast::<v128>().write_unaligned(decoded);
@@ -154,10 +313,6 @@ pub fn decode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
         }
     }
 
-    if failed {
-        return Err(String::from("the decoding process failed unexpectedly"));
-    }
-
     unsafe {
         let new_len = raw_out.offset_from(out.as_ptr());
         out.set_len(new_len as usize);
@@ -165,6 +320,521 @@ pub fn decode_to(data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
 
     Ok(())
 }
+
+/// [`encode_wrapped_to`] appends the padded encoding of `data` to `out`, split into lines of
+/// `line_len` symbols with `line_ending` between lines but not after the last.
+#[cfg(feature = "alloc")]
+pub fn encode_wrapped_to(
+    data: &[u8],
+    line_len: usize,
+    line_ending: len::LineEnding,
+    out: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
+    if line_len == 0 {
+        return Err(EncodeError::InvalidLineLength);
+    }
+
+    let len = len::wrapped_encoded_len(data.len(), true, line_len, line_ending)
+        .ok_or(EncodeError::LengthOverflow)?;
+    let padded_len = len::encoded_len(data.len(), true).ok_or(EncodeError::LengthOverflow)?;
+
+    let start = out.len();
+    out.resize(start + len, 0);
+    let ascii = &mut out[start..];
+
+    // Encode into the tail, then move each line forward past the line endings before it. A
+    // line never lands on symbols that have yet to move.
+    let tail = len - padded_len;
+    let written = encode_symbols(data, &mut ascii[tail..], Alphabet::Standard);
+    ascii[tail + written..].fill(b'=');
+
+    let ending = line_ending.as_bytes();
+    let mut dst = 0;
+    for src in (tail..len).step_by(line_len) {
+        if dst > 0 {
+            ascii[dst..dst + ending.len()].copy_from_slice(ending);
+            dst += ending.len();
+        }
+
+        let end = (src + line_len).min(len);
+        ascii.copy_within(src..end, dst);
+        dst += end - src;
+    }
+
+    Ok(())
+}
+
+/// [`decode_wrapped_to`] is [`decode_to`] skipping ASCII whitespace anywhere in `ascii`, such
+/// as line breaks. Error offsets index into `ascii` itself.
+#[cfg(feature = "alloc")]
+pub fn decode_wrapped_to(ascii: &[u8], out: &mut Vec<u8>) -> Result<(), DecodeError> {
+    let symbols: Vec<u8> = ascii
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+
+    let original = |index: usize| {
+        ascii
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| !b.is_ascii_whitespace())
+            .nth(index)
+            .map_or(index, |(i, _)| i)
+    };
+
+    decode_to(&symbols, out).map_err(|err| err.map_offset(original))
+}
+
+/// [`encode_into_js`] encodes bytes into an existing byte array, returning the number of
+/// bytes written.
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+#[wasm_bindgen(js_name = encode_into)]
+pub fn encode_into_js(data: &[u8], out: &mut [u8]) -> Result<usize, JsValue> {
+    Ok(encode_into(data, out)?)
+}
+
+/// [`decode_into_js`] decodes ascii into an existing byte array, returning the number of
+/// bytes written.
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+#[wasm_bindgen(js_name = decode_into)]
+pub fn decode_into_js(ascii: &[u8], out: &mut [u8]) -> Result<usize, JsValue> {
+    Ok(decode_into(ascii, out)?)
+}
+
+/// [`encode_into`] encodes `data` into the front of `out` and returns the number of bytes
+/// written, padding included. Nothing past that length is touched.
+pub fn encode_into(data: &[u8], out: &mut [u8]) -> Result<usize, EncodeError> {
+    let padded_len = match len::encoded_len(data.len(), true) {
+        Some(padded_len) if padded_len <= out.len() => padded_len,
+        _ => return Err(EncodeError::OutputSliceTooSmall),
+    };
+
+    let written = encode_symbols(data, &mut out[..padded_len], Alphabet::Standard);
+    out[written..padded_len].fill(b'=');
+
+    Ok(padded_len)
+}
+
+/// [`encode_symbols`] writes the unpadded encoding of `data` in `alphabet` to the front of
+/// `out`, which must hold at least `encoded_len(data.len())` bytes.
+pub(crate) fn encode_symbols(data: &[u8], out: &mut [u8], alphabet: Alphabet) -> usize {
+    let len = encoded_len(data.len());
+    let out = &mut out[..len];
+
+    let mut read = 0;
+    let mut written = 0;
+
+    // full 16-byte stores are only safe while both the input load and the output store
+    // stay in bounds, the rest goes through a stack buffer.
+    while read + 16 <= data.len() && written + 16 <= len {
+        let chunk: &[u8; 16] = data[read..read + 16]
+            .try_into()
+            .expect("Slice with incorrect length");
+        let encoded = alphabet.remap_encoded(encode_chunk(chunk));
+
+        unsafe {
+            out.as_mut_ptr()
+                .add(written)
+                .cast::<v128>()
+                .write_unaligned(encoded);
+        }
+
+        read += 12;
+        written += 16;
+    }
+
+    while read < data.len() {
+        let chunk = &data[read..data.len().min(read + 12)];
+
+        let mut temp_chunk = [0u8; 16];
+        temp_chunk[0..chunk.len()].copy_from_slice(chunk);
+
+        let encoded = u8x16_to_array(alphabet.remap_encoded(encode_chunk(&temp_chunk)));
+        let chunk_len = encoded_len(chunk.len());
+        out[written..written + chunk_len].copy_from_slice(&encoded[..chunk_len]);
+
+        read += chunk.len();
+        written += chunk_len;
+    }
+
+    written
+}
+
+/// [`decode_into`] decodes `ascii` into the front of `out` and returns the number of bytes
+/// written. Nothing past that length is touched. A dangling last symbol, or one with nonzero
+/// trailing bits, is rejected.
+pub fn decode_into(ascii: &[u8], out: &mut [u8]) -> Result<usize, DecodeError> {
+    let ascii = match ascii {
+        [p @ .., b'=', b'='] | [p @ .., b'='] | p => p,
+    };
+
+    if ascii.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength(ascii.len()));
+    }
+    engine::check_trailing_bits(ascii, Alphabet::Standard)?;
+
+    if out.len() < decoded_len(ascii.len()) {
+        return Err(DecodeError::OutputSliceTooSmall);
+    }
+
+    decode_symbols(ascii, out, Alphabet::Standard)
+}
+
+/// [`PartialDecoded`] holds the result of [`decode_partial_js`].
+#[cfg(feature = "alloc")]
+#[wasm_bindgen(getter_with_clone)]
+pub struct PartialDecoded {
+    pub read: usize,
+    pub written: usize,
+    pub error: Option<String>,
+}
+
+/// [`decode_partial_js`] decodes the base64 at the front of ascii into an existing byte
+/// array, stopping at the first byte outside the alphabet.
+#[cfg(feature = "alloc")]
+#[wasm_bindgen(js_name = decode_partial)]
+pub fn decode_partial_js(ascii: &[u8], out: &mut [u8]) -> PartialDecoded {
+    let (read, written, error) = decode_partial(ascii, out);
+
+    PartialDecoded {
+        read,
+        written,
+        error: error.map(|err| alloc::string::ToString::to_string(&err)),
+    }
+}
+
+/// [`decode_partial`] decodes the base64 at the front of `ascii` into `out`, stopping at the
+/// first byte outside the alphabet, and returns `(bytes_read, bytes_written, error)`.
+///
+/// A trailing partial quad is decoded, and its padding consumed, only when nothing before it
+/// went wrong. Parsing can resume at `bytes_read`, and `error` says why decoding stopped
+/// there: the byte at that offset, a dangling symbol, or a full `out`. It is `None` once all
+/// of `ascii` is read.
+pub fn decode_partial(ascii: &[u8], out: &mut [u8]) -> (usize, usize, Option<DecodeError>) {
+    let stop = match validate_symbols(ascii, Alphabet::Standard) {
+        Ok(()) => ascii.len(),
+        Err(DecodeError::InvalidByte(index, _)) => index,
+        Err(err) => return (0, 0, Some(err)),
+    };
+
+    let mut error = None;
+    let mut symbols = stop;
+
+    if symbols % 4 == 1 {
+        symbols -= 1;
+        error = Some(DecodeError::InvalidLength(stop));
+    }
+
+    if decoded_len(symbols) > out.len() {
+        symbols = out.len() / 3 * 4;
+        error = Some(DecodeError::OutputSliceTooSmall);
+    }
+
+    let written = decode_symbols(&ascii[..symbols], out, Alphabet::Standard)
+        .expect("Symbols already validated");
+
+    let mut read = symbols;
+    if error.is_none() && symbols % 4 != 0 {
+        let padding = 4 - symbols % 4;
+        if ascii[read..].len() >= padding && ascii[read..read + padding].iter().all(|&b| b == b'=')
+        {
+            read += padding;
+        }
+    }
+
+    if error.is_none() && read < ascii.len() {
+        error = Some(DecodeError::InvalidByte(read, ascii[read]));
+    }
+
+    (read, written, error)
+}
+
+/// [`decode_symbols`] decodes unpadded `ascii` in `alphabet` to the front of `out`, which
+/// must hold at least `decoded_len(ascii.len())` bytes.
+pub(crate) fn decode_symbols(
+    ascii: &[u8],
+    out: &mut [u8],
+    alphabet: Alphabet,
+) -> Result<usize, DecodeError> {
+    decode_symbols_with(ascii, out, alphabet, |_| {})
+}
+
+/// [`decode_symbols_with`] is [`decode_symbols`] handing each run of decoded bytes to
+/// `on_decoded` as soon as it is written, in order.
+pub(crate) fn decode_symbols_with(
+    ascii: &[u8],
+    out: &mut [u8],
+    alphabet: Alphabet,
+    mut on_decoded: impl FnMut(&[u8]),
+) -> Result<usize, DecodeError> {
+    let len = decoded_len(ascii.len());
+    let out = &mut out[..len];
+
+    let mut written = 0;
+    let mut chunks = ascii.chunks_exact(16);
+
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let chunk = alphabet.remap_ascii(chunk.try_into().expect("Slice with incorrect length"));
+        let decoded = decode_chunk(&chunk).map_err(|e| rebase(e, ascii, i * 16))?;
+
+        if written + 16 <= len {
+            unsafe {
+                out.as_mut_ptr()
+                    .add(written)
+                    .cast::<v128>()
+                    .write_unaligned(decoded);
+            }
+        } else {
+            out[written..written + 12].copy_from_slice(&u8x16_to_array(decoded)[..12]);
+        }
+
+        on_decoded(&out[written..written + 12]);
+        written += 12;
+    }
+
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        let mut chunk = [b'A'; 16];
+        chunk[0..rest.len()].copy_from_slice(rest);
+        let decoded = decode_chunk(&alphabet.remap_ascii(&chunk))
+            .map_err(|e| rebase(e, ascii, ascii.len() - rest.len()))?;
+        let decoded = u8x16_to_array(decoded);
+
+        let rest_len = decoded_len(rest.len());
+        out[written..written + rest_len].copy_from_slice(&decoded[..rest_len]);
+        on_decoded(&out[written..written + rest_len]);
+        written += rest_len;
+    }
+
+    Ok(written)
+}
+
+/// [`decode_in_place_js`] decodes a `Uint8Array` of ascii over itself and returns a
+/// `subarray` view of the decoded bytes. The ascii is copied into wasm memory once, decoded
+/// there in place, and written back over the start of `ascii`.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = decode_in_place)]
+pub fn decode_in_place_js(ascii: js_sys::Uint8Array) -> Result<js_sys::Uint8Array, JsValue> {
+    let mut data = ascii.to_vec();
+    let decoded = decode_in_place(&mut data)?;
+
+    let view = ascii.subarray(0, decoded.len() as u32);
+    view.copy_from(decoded);
+    Ok(view)
+}
+
+/// [`decode_in_place`] decodes `ascii` over itself, front to back, and returns the decoded
+/// prefix. Every 16 bytes read become 12 bytes written, so writes never overtake unread
+/// input.
+pub fn decode_in_place(ascii: &mut [u8]) -> Result<&mut [u8], DecodeError> {
+    let len = match &*ascii {
+        [p @ .., b'=', b'='] | [p @ .., b'='] | p => p.len(),
+    };
+
+    let mut read = 0;
+    let mut written = 0;
+
+    while read + 16 <= len {
+        let chunk: [u8; 16] = ascii[read..read + 16]
+            .try_into()
+            .expect("Slice with incorrect length");
+        let decoded = decode_chunk(&chunk).map_err(|e| rebase(e, ascii, read))?;
+
+        // the store ends at `written + 16 <= read + 16`, inside the chunk just loaded.
+        unsafe {
+            ascii
+                .as_mut_ptr()
+                .add(written)
+                .cast::<v128>()
+                .write_unaligned(decoded);
+        }
+
+        read += 16;
+        written += 12;
+    }
+
+    if read < len {
+        let mut chunk = [b'A'; 16];
+        chunk[0..len - read].copy_from_slice(&ascii[read..len]);
+        let decoded = decode_chunk(&chunk).map_err(|e| rebase(e, ascii, read))?;
+        let decoded = u8x16_to_array(decoded);
+
+        let rest_len = decoded_len(len - read);
+        ascii[written..written + rest_len].copy_from_slice(&decoded[..rest_len]);
+        written += rest_len;
+    }
+
+    Ok(&mut ascii[..written])
+}
+
+/// [`LenientDecoded`] holds the result of [`decode_lenient_js`].
+#[cfg(feature = "alloc")]
+#[wasm_bindgen(getter_with_clone)]
+pub struct LenientDecoded {
+    pub bytes: Vec<u8>,
+    pub discarded: usize,
+}
+
+/// [`decode_lenient_js`] decodes ascii after dropping every byte outside the alphabet.
+#[cfg(feature = "alloc")]
+#[wasm_bindgen(js_name = decode_lenient)]
+pub fn decode_lenient_js(ascii: &[u8]) -> Result<LenientDecoded, JsValue> {
+    let (bytes, discarded) = decode_lenient(ascii)?;
+    Ok(LenientDecoded { bytes, discarded })
+}
+
+/// [`decode_lenient`] is [`decode_lenient_in_place`] on a copy of `ascii`.
+#[cfg(feature = "alloc")]
+pub fn decode_lenient(ascii: &[u8]) -> Result<(Vec<u8>, usize), DecodeError> {
+    let mut data = ascii.to_vec();
+    let (decoded, discarded) = decode_lenient_in_place(&mut data)?;
+    let len = decoded.len();
+
+    data.truncate(len);
+    Ok((data, discarded))
+}
+
+/// [`decode_lenient_in_place`] drops every byte of `ascii` outside the alphabet, padding
+/// included, like `base64 -di`, and decodes what is left over itself. It returns the decoded
+/// prefix and the number of bytes dropped.
+pub fn decode_lenient_in_place(ascii: &mut [u8]) -> Result<(&mut [u8], usize), DecodeError> {
+    let len = ascii.len();
+    let mut read = 0;
+    let mut kept = 0;
+
+    while read + 16 <= len {
+        let chunk: [u8; 16] = ascii[read..read + 16]
+            .try_into()
+            .expect("Slice with incorrect length");
+        let (symbols, count) = compact_chunk(&chunk);
+
+        // the store ends at `kept + 16 <= read + 16`, inside the chunk just loaded.
+        unsafe {
+            ascii
+                .as_mut_ptr()
+                .add(kept)
+                .cast::<v128>()
+                .write_unaligned(symbols);
+        }
+
+        read += 16;
+        kept += count;
+    }
+
+    if read < len {
+        let mut chunk = [b'='; 16];
+        chunk[0..len - read].copy_from_slice(&ascii[read..len]);
+        let (symbols, count) = compact_chunk(&chunk);
+
+        ascii[kept..kept + count].copy_from_slice(&u8x16_to_array(symbols)[..count]);
+        kept += count;
+    }
+
+    if kept % 4 == 1 {
+        return Err(DecodeError::InvalidLength(kept));
+    }
+
+    let decoded = decode_in_place(&mut ascii[..kept])?;
+    Ok((decoded, len - kept))
+}
+
+/// [`validate_js`] checks that ascii is valid base64 without decoding it.
+#[cfg(feature = "alloc")]
+#[wasm_bindgen(js_name = validate)]
+pub fn validate_js(ascii: &[u8]) -> Result<(), JsValue> {
+    Ok(validate(ascii)?)
+}
+
+/// [`validate`] checks that `ascii` is valid base64 without writing any decoded bytes.
+/// Padding may be canonical or absent, and the last symbol must not carry trailing bits.
+pub fn validate(ascii: &[u8]) -> Result<(), DecodeError> {
+    const ENGINE: engine::GeneralPurpose = engine::GeneralPurpose::new(
+        &alphabet::STANDARD,
+        engine::GeneralPurposeConfig::new()
+            .with_decode_padding_mode(engine::DecodePaddingMode::Indifferent),
+    );
+
+    ENGINE.validate(ascii)
+}
+
+/// [`validate_symbols`] checks that unpadded `ascii` only holds symbols of `alphabet`.
+pub(crate) fn validate_symbols(ascii: &[u8], alphabet: Alphabet) -> Result<(), DecodeError> {
+    let mut chunks = ascii.chunks_exact(16);
+
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let chunk = alphabet.remap_ascii(chunk.try_into().expect("Slice with incorrect length"));
+        validate_chunk(&chunk).map_err(|e| rebase(e, ascii, i * 16))?;
+    }
+
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        let mut chunk = [b'A'; 16];
+        chunk[0..rest.len()].copy_from_slice(rest);
+        validate_chunk(&alphabet.remap_ascii(&chunk))
+            .map_err(|e| rebase(e, ascii, ascii.len() - rest.len()))?;
+    }
+
+    Ok(())
+}
+
+/// [`text_offset`] maps `index`, into `lines` joined with `sep_len` bytes between each, back
+/// onto `text`, which every line borrows from.
+#[cfg(feature = "alloc")]
+pub(crate) fn text_offset<'a>(
+    text: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+    sep_len: usize,
+    index: usize,
+) -> usize {
+    let mut start = 0;
+    for line in lines {
+        if index < start + line.len() + sep_len {
+            return line.as_ptr() as usize - text.as_ptr() as usize + index - start;
+        }
+        start += line.len() + sep_len;
+    }
+    index
+}
+
+/// [`string_entries`] reads the entries of a JS object as strings, in order.
+#[cfg(feature = "std")]
+pub(crate) fn string_entries(object: Option<js_sys::Object>) -> Vec<(String, String)> {
+    let Some(object) = object else {
+        return Vec::new();
+    };
+
+    js_sys::Object::entries(&object)
+        .iter()
+        .map(|entry| {
+            let entry = js_sys::Array::from(&entry);
+            let name = entry.get(0).as_string().unwrap_or_default();
+            let value = entry.get(1).as_string().unwrap_or_default();
+            (name, value)
+        })
+        .collect()
+}
+
+/// [`entries_object`] builds a JS object with a string property per entry.
+#[cfg(feature = "std")]
+pub(crate) fn entries_object(entries: &[(String, String)]) -> Result<js_sys::Object, JsValue> {
+    let object = js_sys::Object::new();
+    for (name, value) in entries {
+        js_sys::Reflect::set(&object, &name.into(), &value.into())?;
+    }
+
+    Ok(object)
+}
+
+/// [`rebase`] moves an error from [`decode_chunk`] onto the offset of its chunk in `ascii`.
+fn rebase(err: DecodeError, ascii: &[u8], offset: usize) -> DecodeError {
+    match err {
+        DecodeError::InvalidByte(index, _) => {
+            DecodeError::InvalidByte(offset + index, ascii[offset + index])
+        }
+        err => err,
+    }
+}
 #[cfg(test)]
 mod tests {
     use wasm_bindgen_test::wasm_bindgen_test;
@@ -197,4 +867,328 @@ mod tests {
 
         Ok(())
     }
+
+    #[wasm_bindgen_test]
+    fn test_encode_to_string_appends() {
+        let mut header = String::from("Authorization: Basic ");
+        encode_to_string(b"user:pass", &mut header);
+        assert_eq!(header, "Authorization: Basic dXNlcjpwYXNz");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_validate() {
+        let ascii = b"VGhlIGRvZyBsaWNrZWQgdGhlIG9pbCwgYW5kIGV2ZXJ5Ym9keSBsYXVnaGVkLg==";
+
+        assert_eq!(validate(ascii), Ok(()));
+        assert_eq!(validate(&ascii[..ascii.len() - 2]), Ok(()));
+        assert_eq!(validate(b""), Ok(()));
+        assert_eq!(
+            validate(b"VGhlIGRvZyBsaWNrZWQgdGhlIG9pbCwg!W5kIGV2ZXJ5Ym9keSBsYXVnaGVkLg=="),
+            Err(DecodeError::InvalidByte(32, b'!'))
+        );
+        assert_eq!(validate(b"SGVsb"), Err(DecodeError::InvalidLength(5)));
+        assert_eq!(validate(b"SGVsbG8=="), Err(DecodeError::InvalidPadding));
+        assert_eq!(
+            validate(b"SGVsbG8gV29ybGR="),
+            Err(DecodeError::InvalidLastSymbol(14, b'R'))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_in_place() -> Result<(), DecodeError> {
+        let mut ascii = *b"SGVsbG8gV29ybGQ=";
+        assert_eq!(decode_in_place(&mut ascii)?, b"Hello World");
+
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        for len in [0, 1, 11, 12, 13, 24, 500, data.len()] {
+            let mut ascii = engine::general_purpose::STANDARD
+                .encode(&data[..len])
+                .into_bytes();
+            assert_eq!(decode_in_place(&mut ascii)?, &data[..len]);
+        }
+
+        let mut ascii = *b"VGhlIGRvZyBsaWNrZWQgdGhlIG9pbCwg!W5kIGV2ZXJ5Ym9keSBsYXVnaGVkLg==";
+        assert_eq!(
+            decode_in_place(&mut ascii),
+            Err(DecodeError::InvalidByte(32, b'!'))
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_in_place_js() -> Result<(), JsValue> {
+        let ascii = js_sys::Uint8Array::from(&b"SGVsbG8gV29ybGQ="[..]);
+        let view = decode_in_place_js(ascii.clone())?;
+
+        assert_eq!(view.to_vec(), b"Hello World");
+        assert_eq!(view.buffer(), ascii.buffer());
+        assert_eq!(ascii.to_vec()[..11], *b"Hello World");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_lenient() -> Result<(), DecodeError> {
+        let ascii = b"> \"VGhlIGRvZyBsaWNrZWQg\"\r\n> dGhlIG9pbC4=\x07";
+        let (decoded, discarded) = decode_lenient(ascii)?;
+        assert_eq!(decoded, b"The dog licked the oil.");
+        assert_eq!(discarded, 10);
+
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let wrapped: Vec<u8> = engine::general_purpose::STANDARD
+            .encode(&data)
+            .into_bytes()
+            .chunks(76)
+            .flat_map(|line| line.iter().chain(b"\r\n"))
+            .copied()
+            .collect();
+        let (decoded, discarded) = decode_lenient(&wrapped)?;
+        assert_eq!(decoded, data);
+        assert_eq!(discarded, 18 * 2 + 2);
+
+        assert_eq!(decode_lenient(b"!!S!!"), Err(DecodeError::InvalidLength(1)));
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_partial() {
+        let mut out = [0u8; 32];
+
+        let ascii = b"SGVsbG8gV29ybGQ=\", rest";
+        assert_eq!(
+            decode_partial(ascii, &mut out),
+            (16, 11, Some(DecodeError::InvalidByte(16, b'"')))
+        );
+        assert_eq!(&out[..11], b"Hello World");
+
+        let ascii = b"SGVsbG8gV29ybGQ\"";
+        assert_eq!(
+            decode_partial(ascii, &mut out),
+            (15, 11, Some(DecodeError::InvalidByte(15, b'"')))
+        );
+
+        assert_eq!(
+            decode_partial(b"SGVsbG8gV29ybGQ=", &mut out),
+            (16, 11, None)
+        );
+        assert_eq!(
+            decode_partial(b"SGVsbG8gV29ybGQ==", &mut out),
+            (16, 11, Some(DecodeError::InvalidByte(16, b'=')))
+        );
+        assert_eq!(
+            decode_partial(b"SGVsbG8gV29ybGQ=", &mut out[..7]),
+            (8, 6, Some(DecodeError::OutputSliceTooSmall))
+        );
+        assert_eq!(
+            decode_partial(b"SGVsb,", &mut out),
+            (4, 3, Some(DecodeError::InvalidLength(5)))
+        );
+
+        let data: Vec<u8> = (0..=255u8).cycle().take(300).collect();
+        let mut ascii = engine::general_purpose::STANDARD_NO_PAD
+            .encode(&data)
+            .into_bytes();
+        let len = ascii.len();
+        ascii.extend_from_slice(b"</data>");
+
+        let mut out = vec![0u8; data.len()];
+        assert_eq!(
+            decode_partial(&ascii, &mut out),
+            (len, data.len(), Some(DecodeError::InvalidByte(len, b'<')))
+        );
+        assert_eq!(out, data);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_encode_vectored() {
+        use std::io::IoSlice;
+
+        let data: Vec<u8> = (0..=255u8).cycle().take(300).collect();
+        let expected = engine::general_purpose::STANDARD.encode(&data);
+
+        for splits in [
+            [0, 0, 300],
+            [1, 2, 3],
+            [5, 17, 40],
+            [11, 12, 13],
+            [150, 299, 300],
+        ] {
+            let bufs = [
+                IoSlice::new(&data[..splits[0]]),
+                IoSlice::new(&data[splits[0]..splits[1]]),
+                IoSlice::new(&data[splits[1]..splits[2]]),
+                IoSlice::new(&data[splits[2]..]),
+            ];
+
+            assert_eq!(encode_vectored(&bufs), expected.as_bytes());
+        }
+
+        let bufs = [
+            IoSlice::new(b"Hello"),
+            IoSlice::new(b" "),
+            IoSlice::new(b"World"),
+        ];
+        assert_eq!(encode_vectored(&bufs), b"SGVsbG8gV29ybGQ=");
+        assert_eq!(encode_vectored(&[]), b"");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_stream_encoder_matches_engine() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+
+        for piece in [1, 2, 3, 5, 16, 64, 999, 1000] {
+            for len in [0, 1, 2, 3, 4, 100, 1000] {
+                let mut ascii = Vec::new();
+                let mut encoder = StreamEncoder::new(&mut ascii);
+                data[..len]
+                    .chunks(piece)
+                    .for_each(|piece| encoder.push(piece));
+                encoder.finish();
+
+                assert_eq!(
+                    ascii,
+                    engine::general_purpose::STANDARD
+                        .encode(&data[..len])
+                        .as_bytes()
+                );
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_stream_encoder_skips_empty_pieces() {
+        let mut ascii = Vec::new();
+        let mut encoder = StreamEncoder::new(&mut ascii);
+        for piece in [&b"f"[..], b"", b"o", b"", b"ob", b""] {
+            encoder.push(piece);
+        }
+        encoder.finish();
+
+        assert_eq!(ascii, b"Zm9vYg==");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_wrapped_round_trip() -> Result<(), DecodeError> {
+        let data = b"The dog licked the oil, and everybody laughed.";
+
+        let mut ascii = Vec::new();
+        assert_eq!(
+            encode_wrapped_to(data, 16, len::LineEnding::CrLf, &mut ascii),
+            Ok(())
+        );
+        assert_eq!(
+            ascii,
+            b"VGhlIGRvZyBsaWNr\r\nZWQgdGhlIG9pbCwg\r\nYW5kIGV2ZXJ5Ym9k\r\neSBsYXVnaGVkLg=="
+        );
+
+        let mut decoded = Vec::new();
+        decode_wrapped_to(&ascii, &mut decoded)?;
+        assert_eq!(decoded, data);
+
+        let mut decoded = Vec::new();
+        assert_eq!(
+            decode_wrapped_to(b" Zm9v\n\tY!Fy ", &mut decoded),
+            Err(DecodeError::InvalidByte(8, b'!'))
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_encode_wrapped_line_lengths() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(100).collect();
+        let padded = engine::general_purpose::STANDARD.encode(&data);
+
+        for line_len in [1, 3, 4, 5, 63, 64, 133, 134, 200] {
+            for line_ending in [len::LineEnding::Lf, len::LineEnding::CrLf] {
+                let expected = padded
+                    .as_bytes()
+                    .chunks(line_len)
+                    .collect::<Vec<_>>()
+                    .join(line_ending.as_bytes());
+
+                let mut ascii = b"prefix".to_vec();
+                assert_eq!(
+                    encode_wrapped_to(&data, line_len, line_ending, &mut ascii),
+                    Ok(())
+                );
+                assert_eq!(&ascii[..6], b"prefix");
+                assert_eq!(&ascii[6..], expected);
+            }
+        }
+
+        let mut ascii = Vec::new();
+        assert_eq!(
+            encode_wrapped_to(&data, 0, len::LineEnding::Lf, &mut ascii),
+            Err(EncodeError::InvalidLineLength)
+        );
+        assert_eq!(
+            encode_wrapped_to(b"", 4, len::LineEnding::Lf, &mut ascii),
+            Ok(())
+        );
+        assert!(ascii.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_encode_into_exact_len() -> Result<(), EncodeError> {
+        let data = b"The dog licked the oil, and everybody laughed.";
+        let ascii = b"VGhlIGRvZyBsaWNrZWQgdGhlIG9pbCwgYW5kIGV2ZXJ5Ym9keSBsYXVnaGVkLg==";
+
+        let mut out = [0u8; 64];
+        assert_eq!(encode_into(data, &mut out)?, ascii.len());
+        assert_eq!(&out, ascii);
+
+        let mut out = [b'!'; 72];
+        assert_eq!(encode_into(b"Hello World", &mut out)?, 16);
+        assert_eq!(&out[..16], b"SGVsbG8gV29ybGQ=");
+        assert!(out[16..].iter().all(|&b| b == b'!'));
+
+        assert_eq!(
+            encode_into(data, &mut [0u8; 63]),
+            Err(EncodeError::OutputSliceTooSmall)
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_into_exact_len() -> Result<(), DecodeError> {
+        let ascii = b"VGhlIGRvZyBsaWNrZWQgdGhlIG9pbCwgYW5kIGV2ZXJ5Ym9keSBsYXVnaGVkLg==";
+        let data = b"The dog licked the oil, and everybody laughed.";
+
+        let mut out = [b'!'; 48];
+        assert_eq!(decode_into(ascii, &mut out)?, data.len());
+        assert_eq!(&out[..data.len()], data);
+        assert!(out[data.len()..].iter().all(|&b| b == b'!'));
+
+        let mut out = [0u8; 46];
+        assert_eq!(decode_into(ascii, &mut out)?, data.len());
+        assert_eq!(&out, data);
+
+        assert_eq!(
+            decode_into(ascii, &mut [0u8; 45]),
+            Err(DecodeError::OutputSliceTooSmall)
+        );
+        assert_eq!(
+            decode_into(b"SGVsbG8*V29ybGQ=", &mut [0u8; 16]),
+            Err(DecodeError::InvalidByte(7, b'*'))
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_into_rejects_dangling_and_trailing_bits() {
+        let mut out = [0u8; 16];
+
+        assert_eq!(
+            decode_into(b"A", &mut out),
+            Err(DecodeError::InvalidLength(1))
+        );
+        assert_eq!(
+            decode_into(b"SGVsbG8gV=", &mut out),
+            Err(DecodeError::InvalidLength(9))
+        );
+        assert_eq!(
+            decode_into(b"SGVsbG8gV29ybGR=", &mut out),
+            Err(DecodeError::InvalidLastSymbol(14, b'R'))
+        );
+        assert_eq!(decode_into(b"SGVsbG8gV29ybGQ=", &mut out), Ok(11));
+    }
 }
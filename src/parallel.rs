@@ -0,0 +1,141 @@
+//! Encode and decode large buffers across a rayon thread pool.
+//!
+//! Input is split on 3-byte boundaries for encoding and 4-symbol boundaries for decoding, so
+//! each task runs the usual chunk loops on its own slice and writes a disjoint slice of the
+//! output.
+//!
+//! The pool is a Web Worker per thread, started from JS with [`init_thread_pool`], exported as
+//! `initThreadPool`, before the first parallel call; once rayon has run anything the pool
+//! can no longer be replaced. Workers share the module's memory, so the crate has to be built
+//! with `-C target-feature=+atomics,+bulk-memory` and `-Z build-std=panic_abort,std` on
+//! nightly, and the page served cross-origin isolated:
+//!
+//! ```js
+//! import init, { initThreadPool, encode_parallel } from "./pkg/based64.js";
+//!
+//! await init();
+//! await initThreadPool(navigator.hardwareConcurrency);
+//! const ascii = encode_parallel(bytes);
+//! ```
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+use crate::alphabet::Alphabet;
+use crate::buffer_source::byte_view;
+use crate::{decode_symbols, decoded_len, encode_symbols, len, rebase, DecodeError};
+
+/// Input bytes per encoding task, a multiple of 12 so only the last task ends in a partial
+/// group.
+const ENCODE_SPLIT: usize = 12 * 64 * 1024;
+
+/// Symbols per decoding task, a multiple of 16 so only the last task ends in a partial
+/// chunk.
+const DECODE_SPLIT: usize = ENCODE_SPLIT / 3 * 4;
+
+/// [`encode_parallel_js`] encodes any `BufferSource` across the thread pool.
+#[wasm_bindgen(js_name = encode_parallel)]
+pub fn encode_parallel_js(data: &JsValue) -> Result<Vec<u8>, JsValue> {
+    Ok(encode_parallel(&byte_view(data)?.to_vec()))
+}
+
+/// [`decode_parallel_js`] decodes ascii across the thread pool.
+#[wasm_bindgen(js_name = decode_parallel)]
+pub fn decode_parallel_js(ascii: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(decode_parallel(ascii)?)
+}
+
+/// [`encode_parallel`] encodes `data` with padding, one task per [`ENCODE_SPLIT`] bytes.
+pub fn encode_parallel(data: &[u8]) -> Vec<u8> {
+    let len =
+        len::encoded_len(data.len(), true).expect("usize overflow when calculating encoded length");
+    let mut ascii = vec![0u8; len];
+
+    data.par_chunks(ENCODE_SPLIT)
+        .zip(ascii.par_chunks_mut(DECODE_SPLIT))
+        .for_each(|(data, ascii)| {
+            let written = encode_symbols(data, ascii, Alphabet::Standard);
+            ascii[written..].fill(b'=');
+        });
+
+    ascii
+}
+
+/// [`decode_parallel`] decodes `ascii`, with or without padding, one task per
+/// [`DECODE_SPLIT`] symbols. Like [`decode`](crate::decode_to), an error names the first
+/// invalid byte in `ascii`.
+pub fn decode_parallel(ascii: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let ascii = match ascii {
+        [p @ .., b'=', b'='] | [p @ .., b'='] | p => p,
+    };
+
+    let mut data = vec![0u8; decoded_len(ascii.len())];
+
+    let results: Vec<_> = ascii
+        .par_chunks(DECODE_SPLIT)
+        .zip(data.par_chunks_mut(ENCODE_SPLIT))
+        .enumerate()
+        .map(|(i, (chunk, data))| {
+            decode_symbols(chunk, data, Alphabet::Standard)
+                .map(|_| ())
+                .map_err(|e| rebase(e, ascii, i * DECODE_SPLIT))
+        })
+        .collect();
+
+    results.into_iter().collect::<Result<(), _>>()?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[cfg(target_feature = "atomics")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    use super::*;
+    use crate::engine::general_purpose::STANDARD;
+    use crate::engine::Engine;
+
+    #[cfg_attr(not(target_feature = "atomics"), wasm_bindgen_test)]
+    fn test_matches_engine_across_splits() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(ENCODE_SPLIT * 2 + 7).collect();
+
+        for len in [0, 1, 11, ENCODE_SPLIT, ENCODE_SPLIT + 1, data.len()] {
+            let bytes = &data[..len];
+            let ascii = encode_parallel(bytes);
+
+            assert_eq!(ascii, STANDARD.encode(bytes).as_bytes());
+            assert_eq!(decode_parallel(&ascii), Ok(bytes.to_vec()));
+        }
+    }
+
+    #[cfg_attr(not(target_feature = "atomics"), wasm_bindgen_test)]
+    fn test_reports_first_invalid_byte() {
+        let data = vec![0u8; ENCODE_SPLIT * 3];
+        let mut ascii = encode_parallel(&data);
+
+        ascii[DECODE_SPLIT * 2 + 5] = b'!';
+        ascii[DECODE_SPLIT + 3] = b'*';
+
+        assert_eq!(
+            decode_parallel(&ascii),
+            Err(DecodeError::InvalidByte(DECODE_SPLIT + 3, b'*'))
+        );
+    }
+
+    /// The atomics build runs the tests above on a started pool, which has to come first, in a
+    /// cross-origin isolated browser that can start the workers.
+    #[cfg(target_feature = "atomics")]
+    #[wasm_bindgen_test]
+    async fn test_with_thread_pool() -> Result<(), JsValue> {
+        wasm_bindgen_futures::JsFuture::from(init_thread_pool(4)).await?;
+        assert_eq!(rayon::current_num_threads(), 4);
+
+        test_matches_engine_across_splits();
+        test_reports_first_invalid_byte();
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+//! Mirrors `base64::prelude`: `use based64::prelude::*;` brings the [`Engine`] methods and the
+//! standard engines into scope.
+//!
+//! [`Engine`]: crate::engine::Engine
+
+pub use crate::engine::general_purpose::{
+    STANDARD as BASE64_STANDARD, STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD,
+    URL_SAFE as BASE64_URL_SAFE, URL_SAFE_NO_PAD as BASE64_URL_SAFE_NO_PAD,
+};
+pub use crate::engine::Engine as _;
@@ -0,0 +1,321 @@
+//! Async JS codecs for large inputs. They work a slice at a time, yield to the event loop in
+//! between, report progress and stop when an `AbortSignal` fires.
+
+use js_sys::{Error, Function, Promise, Reflect, Uint8Array};
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::alphabet::Alphabet;
+use crate::buffer_source::byte_view;
+use crate::{decode_symbols, decoded_len, encode_to, rebase, DecodeError, EncodeError};
+
+/// Input bytes per encoding step, a multiple of 12 so no step but the last is padded.
+const ENCODE_STEP: usize = 12 * 64 * 1024;
+
+/// Symbols per decoding step, a multiple of 16 so no step but the last has a partial chunk.
+const DECODE_STEP: usize = 16 * 64 * 1024;
+
+#[wasm_bindgen]
+extern "C" {
+    /// The subset of `AbortSignal` read between steps.
+    #[wasm_bindgen(js_name = AbortSignal)]
+    pub type AbortSignal;
+
+    #[wasm_bindgen(method, getter)]
+    fn aborted(this: &AbortSignal) -> bool;
+
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &Function, timeout: i32) -> JsValue;
+}
+
+/// [`encode_async`] encodes any `BufferSource` a slice at a time. After each slice it calls
+/// `on_progress(bytesDone, total)` and yields to the event loop. If `signal` aborts before it
+/// resolves, even after the last slice, it rejects with an `AbortError` whose `partial`
+/// property holds the output so far.
+#[wasm_bindgen]
+pub async fn encode_async(
+    data: JsValue,
+    on_progress: Option<Function>,
+    signal: Option<AbortSignal>,
+) -> Result<Vec<u8>, JsValue> {
+    let data = byte_view(&data)?.to_vec();
+
+    let mut ascii = Vec::new();
+    let mut done = 0;
+
+    while done < data.len() {
+        check_aborted(signal.as_ref(), &ascii)?;
+
+        done = encode_step(&data, done, &mut ascii)?;
+        report(on_progress.as_ref(), done, data.len())?;
+        yield_now().await?;
+    }
+
+    check_aborted(signal.as_ref(), &ascii)?;
+    Ok(ascii)
+}
+
+/// [`decode_async`] decodes `ascii` a slice at a time, like [`encode_async`].
+#[wasm_bindgen]
+pub async fn decode_async(
+    ascii: Vec<u8>,
+    on_progress: Option<Function>,
+    signal: Option<AbortSignal>,
+) -> Result<Vec<u8>, JsValue> {
+    let symbols = match ascii.as_slice() {
+        [p @ .., b'=', b'='] | [p @ .., b'='] | p => p,
+    };
+
+    let mut data = Vec::new();
+    let mut done = 0;
+
+    while done < symbols.len() {
+        check_aborted(signal.as_ref(), &data)?;
+
+        done = decode_step(symbols, done, &mut data)?;
+        report(on_progress.as_ref(), done, symbols.len())?;
+        yield_now().await?;
+    }
+
+    check_aborted(signal.as_ref(), &data)?;
+    Ok(data)
+}
+
+/// [`encode_step`] appends the encoding of the next slice of `data`, from `done` on, to `out`
+/// and returns how far it got.
+fn encode_step(data: &[u8], done: usize, out: &mut Vec<u8>) -> Result<usize, EncodeError> {
+    let end = data.len().min(done + ENCODE_STEP);
+    encode_to(&data[done..end], out)?;
+
+    Ok(end)
+}
+
+/// [`decode_step`] appends the decoding of the next slice of unpadded `symbols`, from `done`
+/// on, to `out` and returns how far it got.
+fn decode_step(symbols: &[u8], done: usize, out: &mut Vec<u8>) -> Result<usize, DecodeError> {
+    let end = symbols.len().min(done + DECODE_STEP);
+    let start = out.len();
+
+    out.resize(start + decoded_len(end - done), 0);
+    decode_symbols(&symbols[done..end], &mut out[start..], Alphabet::Standard).map_err(|e| {
+        out.truncate(start);
+        rebase(e, symbols, done)
+    })?;
+
+    Ok(end)
+}
+
+fn check_aborted(signal: Option<&AbortSignal>, partial: &[u8]) -> Result<(), JsValue> {
+    match signal {
+        Some(signal) if signal.aborted() => {
+            let err = Error::new("The operation was aborted");
+            err.set_name("AbortError");
+            Reflect::set(&err, &"partial".into(), &Uint8Array::from(partial))?;
+
+            Err(err.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn report(on_progress: Option<&Function>, done: usize, total: usize) -> Result<(), JsValue> {
+    if let Some(on_progress) = on_progress {
+        on_progress.call2(&JsValue::NULL, &done.into(), &total.into())?;
+    }
+
+    Ok(())
+}
+
+/// [`yield_now`] resolves on a `setTimeout(0)`, letting the event loop run rendering and
+/// other tasks, which a resolved promise alone would not.
+async fn yield_now() -> Result<(), JsValue> {
+    let promise = Promise::new(&mut |resolve, _| {
+        set_timeout(&resolve, 0);
+    });
+
+    JsFuture::from(promise).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::engine::general_purpose::STANDARD;
+    use crate::engine::Engine;
+
+    #[wasm_bindgen]
+    extern "C" {
+        type AbortController;
+
+        #[wasm_bindgen(constructor)]
+        fn new() -> AbortController;
+
+        #[wasm_bindgen(method, getter)]
+        fn signal(this: &AbortController) -> AbortSignal;
+
+        #[wasm_bindgen(method)]
+        fn abort(this: &AbortController);
+    }
+
+    /// [`Progress`] records every `on_progress` call, and aborts `controller` on call
+    /// number `abort_at`, if given.
+    struct Progress {
+        calls: Rc<RefCell<Vec<(usize, usize)>>>,
+        callback: Closure<dyn FnMut(usize, usize)>,
+    }
+
+    impl Progress {
+        fn new(controller: Option<Rc<AbortController>>, abort_at: usize) -> Self {
+            let calls = Rc::new(RefCell::new(Vec::new()));
+            let recorded = calls.clone();
+
+            let callback = Closure::new(move |done, total| {
+                recorded.borrow_mut().push((done, total));
+                if let Some(controller) = &controller {
+                    if recorded.borrow().len() == abort_at {
+                        controller.abort();
+                    }
+                }
+            });
+
+            Self { calls, callback }
+        }
+
+        fn function(&self) -> Option<Function> {
+            Some(self.callback.as_ref().unchecked_ref::<Function>().clone())
+        }
+    }
+
+    fn partial(err: JsValue) -> Result<Vec<u8>, JsValue> {
+        let err: Error = err.dyn_into()?;
+        assert_eq!(String::from(err.name()), "AbortError");
+
+        let partial: Uint8Array = Reflect::get(&err, &"partial".into())?.dyn_into()?;
+        Ok(partial.to_vec())
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_async_round_trip_reports_progress() -> Result<(), JsValue> {
+        let data: Vec<u8> = (0..=255u8).cycle().take(ENCODE_STEP * 2 + 5).collect();
+
+        let progress = Progress::new(None, 0);
+        let ascii = encode_async(
+            Uint8Array::from(&data[..]).into(),
+            progress.function(),
+            None,
+        )
+        .await?;
+        assert_eq!(ascii, STANDARD.encode(&data).as_bytes());
+        assert_eq!(
+            *progress.calls.borrow(),
+            [
+                (ENCODE_STEP, data.len()),
+                (ENCODE_STEP * 2, data.len()),
+                (data.len(), data.len())
+            ]
+        );
+
+        let symbols = ascii.len() - 1;
+        let progress = Progress::new(None, 0);
+        let decoded = decode_async(ascii, progress.function(), None).await?;
+        assert_eq!(decoded, data);
+        assert_eq!(
+            *progress.calls.borrow(),
+            [
+                (DECODE_STEP, symbols),
+                (DECODE_STEP * 2, symbols),
+                (symbols, symbols)
+            ]
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_abort_rejects_with_partial() -> Result<(), JsValue> {
+        let data: Vec<u8> = (0..=255u8).cycle().take(ENCODE_STEP * 2 + 5).collect();
+        let ascii = STANDARD.encode(&data);
+
+        let controller = Rc::new(AbortController::new());
+        let progress = Progress::new(Some(controller.clone()), 1);
+        let err = encode_async(
+            Uint8Array::from(&data[..]).into(),
+            progress.function(),
+            Some(controller.signal()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(partial(err)?, ascii.as_bytes()[..ENCODE_STEP / 3 * 4]);
+        assert_eq!(progress.calls.borrow().len(), 1);
+
+        let controller = Rc::new(AbortController::new());
+        let progress = Progress::new(Some(controller.clone()), 2);
+        let err = decode_async(
+            ascii.clone().into_bytes(),
+            progress.function(),
+            Some(controller.signal()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(partial(err)?, data[..DECODE_STEP / 4 * 3 * 2]);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_abort_during_last_yield_rejects() -> Result<(), JsValue> {
+        let data = vec![7u8; 100];
+
+        let controller = Rc::new(AbortController::new());
+        let progress = Progress::new(Some(controller.clone()), 1);
+        let err = encode_async(
+            Uint8Array::from(&data[..]).into(),
+            progress.function(),
+            Some(controller.signal()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(partial(err)?, STANDARD.encode(&data).as_bytes());
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_steps_match_engine() -> Result<(), DecodeError> {
+        let data: Vec<u8> = (0..=255u8).cycle().take(ENCODE_STEP * 2 + 5).collect();
+
+        let mut ascii = Vec::new();
+        let mut done = 0;
+        while done < data.len() {
+            done = encode_step(&data, done, &mut ascii).unwrap();
+        }
+        assert_eq!(ascii, STANDARD.encode(&data).as_bytes());
+
+        let symbols = ascii.strip_suffix(b"=").unwrap_or(&ascii);
+        let mut decoded = Vec::new();
+        let mut done = 0;
+        while done < symbols.len() {
+            done = decode_step(symbols, done, &mut decoded)?;
+        }
+        assert_eq!(decoded, data);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_step_keeps_earlier_output_on_error() {
+        let mut symbols = STANDARD.encode(vec![0u8; DECODE_STEP]).into_bytes();
+        symbols[DECODE_STEP + 2] = b'!';
+
+        let mut data = Vec::new();
+        assert_eq!(decode_step(&symbols, 0, &mut data), Ok(DECODE_STEP));
+        assert_eq!(
+            decode_step(&symbols, DECODE_STEP, &mut data),
+            Err(DecodeError::InvalidByte(DECODE_STEP + 2, b'!'))
+        );
+        assert_eq!(data.len(), DECODE_STEP / 4 * 3);
+    }
+}
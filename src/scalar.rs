@@ -0,0 +1,200 @@
+//! Scalar `const fn` codecs for base64 known at compile time, see [`decode!`](crate::decode).
+
+use crate::alphabet::Alphabet;
+use crate::DecodeError;
+
+const SYMBOLS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// [`decode!`](crate::decode) decodes a base64 string literal at compile time into a
+/// `&'static [u8; N]`. An invalid literal is a compile error.
+///
+/// ```
+/// const KEY: &[u8; 11] = based64::decode!("SGVsbG8gV29ybGQ=");
+/// assert_eq!(KEY, b"Hello World");
+/// ```
+///
+/// A symbol outside the alphabet, bad padding or nonzero trailing bits fail the build:
+///
+/// ```compile_fail
+/// const KEY: &[u8; 1] = based64::decode!("Zg=A");
+/// ```
+#[macro_export]
+macro_rules! decode {
+    ($ascii:literal) => {{
+        const ASCII: &[u8] = $ascii.as_bytes();
+        const DECODED: [u8; $crate::scalar::decoded_len(ASCII)] =
+            $crate::scalar::decode_literal(ASCII);
+        &DECODED
+    }};
+}
+
+/// [`encoded_len`] returns the padded length `data_len` bytes encode to.
+pub const fn encoded_len(data_len: usize) -> usize {
+    match crate::len::encoded_len(data_len, true) {
+        Some(len) => len,
+        None => panic!("usize overflow when calculating encoded length"),
+    }
+}
+
+/// [`decoded_len`] returns the number of bytes `ascii` decodes to, padding excluded.
+pub const fn decoded_len(ascii: &[u8]) -> usize {
+    crate::decode_chunk::decoded_len(unpadded_len(ascii))
+}
+
+/// [`encode`] encodes `data` with padding. `N` must be [`encoded_len`] of `data.len()`.
+pub const fn encode<const N: usize>(data: &[u8]) -> [u8; N] {
+    assert!(
+        N == encoded_len(data.len()),
+        "N must equal encoded_len(data.len())"
+    );
+
+    let mut out = [b'='; N];
+    let mut read = 0;
+    let mut written = 0;
+
+    while read < data.len() {
+        let mut group = 0u32;
+        let mut j = 0;
+        while j < 3 {
+            let byte = if read + j < data.len() {
+                data[read + j]
+            } else {
+                0
+            };
+            group = group << 8 | byte as u32;
+            j += 1;
+        }
+
+        let symbols = crate::encode_chunk::encoded_len(data.len() - read);
+        let mut i = 0;
+        while i < 4 && i < symbols {
+            out[written + i] = SYMBOLS[(group >> (18 - 6 * i) & 0x3F) as usize];
+            i += 1;
+        }
+
+        read += 3;
+        written += 4;
+    }
+
+    out
+}
+
+/// [`decode`] decodes `ascii`, with or without canonical padding. `N` must be
+/// [`decoded_len`] of `ascii`.
+pub const fn decode<const N: usize>(ascii: &[u8]) -> Result<[u8; N], DecodeError> {
+    let len = unpadded_len(ascii);
+    let padding = ascii.len() - len;
+
+    if len % 4 == 1 {
+        return Err(DecodeError::InvalidLength(len));
+    }
+
+    if padding != 0 && padding != (4 - len % 4) % 4 {
+        return Err(DecodeError::InvalidPadding);
+    }
+
+    assert!(N == decoded_len(ascii), "N must equal decoded_len(ascii)");
+
+    let mut out = [0u8; N];
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut written = 0;
+    let mut i = 0;
+
+    while i < len {
+        let sextet = match Alphabet::Standard.sextet(ascii[i]) {
+            Some(sextet) => sextet,
+            None => return Err(DecodeError::InvalidByte(i, ascii[i])),
+        };
+
+        acc = acc << 6 | sextet as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            out[written] = (acc >> bits) as u8;
+            written += 1;
+        }
+
+        i += 1;
+    }
+
+    if acc & ((1 << bits) - 1) != 0 {
+        return Err(DecodeError::InvalidLastSymbol(len - 1, ascii[len - 1]));
+    }
+
+    Ok(out)
+}
+
+/// [`decode_literal`] backs [`decode!`](crate::decode), turning each error into a const
+/// panic and so a compile error.
+#[doc(hidden)]
+pub const fn decode_literal<const N: usize>(ascii: &[u8]) -> [u8; N] {
+    match decode(ascii) {
+        Ok(decoded) => decoded,
+        Err(DecodeError::InvalidByte(..)) => panic!("invalid base64 literal: invalid byte"),
+        Err(DecodeError::InvalidLength(_)) => panic!("invalid base64 literal: invalid length"),
+        Err(DecodeError::InvalidLastSymbol(..)) => {
+            panic!("invalid base64 literal: nonzero trailing bits")
+        }
+        Err(DecodeError::InvalidPadding) => panic!("invalid base64 literal: invalid padding"),
+        Err(DecodeError::InvalidSymbols | DecodeError::OutputSliceTooSmall) => unreachable!(),
+    }
+}
+
+const fn unpadded_len(ascii: &[u8]) -> usize {
+    let mut len = ascii.len();
+    while len > 0 && ascii[len - 1] == b'=' {
+        len -= 1;
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_decode_macro() {
+        const HELLO: &[u8; 11] = crate::decode!("SGVsbG8gV29ybGQ=");
+        let unpadded: &'static [u8; 11] = crate::decode!("SGVsbG8gV29ybGQ");
+
+        assert_eq!(HELLO, b"Hello World");
+        assert_eq!(unpadded, b"Hello World");
+        assert_eq!(crate::decode!(""), &[0u8; 0]);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rfc4648_vectors() {
+        const FOOBAR: [u8; encoded_len(6)] = encode(b"foobar");
+        assert_eq!(&FOOBAR, b"Zm9vYmFy");
+
+        assert_eq!(&encode::<0>(b""), b"");
+        assert_eq!(&encode::<4>(b"f"), b"Zg==");
+        assert_eq!(&encode::<4>(b"fo"), b"Zm8=");
+        assert_eq!(&encode::<4>(b"foo"), b"Zm9v");
+        assert_eq!(&encode::<8>(b"foob"), b"Zm9vYg==");
+        assert_eq!(&encode::<8>(b"fooba"), b"Zm9vYmE=");
+
+        assert_eq!(decode::<1>(b"Zg=="), Ok(*b"f"));
+        assert_eq!(decode::<2>(b"Zm8="), Ok(*b"fo"));
+        assert_eq!(decode::<4>(b"Zm9vYg"), Ok(*b"foob"));
+        assert_eq!(decode::<6>(b"Zm9vYmFy"), Ok(*b"foobar"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_const_decode_errors() {
+        assert_eq!(
+            decode::<11>(b"SGVsbG8*V29ybGQ="),
+            Err(DecodeError::InvalidByte(7, b'*'))
+        );
+        assert_eq!(decode::<3>(b"SGVsb"), Err(DecodeError::InvalidLength(5)));
+        assert_eq!(decode::<1>(b"SG="), Err(DecodeError::InvalidPadding));
+        assert_eq!(
+            decode::<11>(b"SGVsbG8gV29ybGR="),
+            Err(DecodeError::InvalidLastSymbol(14, b'R'))
+        );
+    }
+}
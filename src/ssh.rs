@@ -0,0 +1,386 @@
+//! Parse OpenSSH public key lines, `<type> <base64 blob> [comment]`, as found in
+//! `authorized_keys`, `known_hosts` and `.pub` files.
+//!
+//! The blob is a sequence of length-prefixed strings ([RFC 4253 §6.6]) whose first is the key
+//! type again, which has to match the one in front of it.
+//!
+//! [RFC 4253 §6.6]: https://www.rfc-editor.org/rfc/rfc4253#section-6.6
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use js_sys::{Object, Reflect, Uint8Array};
+#[cfg(feature = "std")]
+use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "std")]
+use wasm_bindgen::JsValue;
+
+use crate::{decode_to, DecodeError};
+
+/// [`PublicKey`] is one parsed key line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    /// The `authorized_keys` options, or `known_hosts` marker and host patterns, in front of
+    /// the key type. Empty if there are none.
+    pub prefix: String,
+    pub key_type: String,
+    pub key: KeyData,
+    pub comment: String,
+    /// The decoded blob, exactly as it appears in the line.
+    pub blob: Vec<u8>,
+}
+
+/// [`KeyData`] holds the fields of the key types whose layout is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyData {
+    Ed25519([u8; 32]),
+    /// The exponent and modulus, as big-endian two's complement `mpint`s.
+    Rsa {
+        e: Vec<u8>,
+        n: Vec<u8>,
+    },
+    /// The curve name, such as `nistp256`, and the encoded public point.
+    Ecdsa {
+        curve: String,
+        point: Vec<u8>,
+    },
+    /// Certificates, security keys and other types, only available as the blob.
+    Other,
+}
+
+/// [`SshKeyError`] describes why a key line could not be parsed. Columns are byte offsets into
+/// the line, from zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SshKeyError {
+    /// Nothing on the line, from the given column on, looks like a key type.
+    MissingKeyType(usize),
+    /// The key type at the given column is not followed by a blob.
+    MissingBlob(usize),
+    /// The blob at the given column is not base64. The error's offsets are columns too.
+    InvalidBase64(usize, DecodeError),
+    /// The blob at the given column ends inside a length-prefixed field, or has bytes left over.
+    Malformed(usize),
+    /// The key type inside the blob at the given column differs from the one in the line.
+    TypeMismatch(usize),
+}
+
+impl fmt::Display for SshKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingKeyType(column) => write!(f, "missing key type after column {column}"),
+            Self::MissingBlob(column) => write!(f, "missing key blob after column {column}"),
+            Self::InvalidBase64(column, err) => {
+                write!(f, "invalid base64 blob at column {column}: {err}")
+            }
+            Self::Malformed(column) => write!(f, "malformed key blob at column {column}"),
+            Self::TypeMismatch(column) => write!(f, "key type mismatch in blob at column {column}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SshKeyError {}
+
+#[cfg(feature = "std")]
+impl From<SshKeyError> for JsValue {
+    fn from(err: SshKeyError) -> Self {
+        Self::from_str(&err.to_string())
+    }
+}
+
+/// [`parse_ssh_public_key_js`] parses a key line into `{ prefix, type, blob, comment }`.
+#[cfg(feature = "std")]
+#[wasm_bindgen(js_name = parse_ssh_public_key)]
+pub fn parse_ssh_public_key_js(line: &str) -> Result<Object, JsValue> {
+    let PublicKey {
+        prefix,
+        key_type,
+        comment,
+        blob,
+        ..
+    } = parse(line)?;
+
+    let key = Object::new();
+    Reflect::set(&key, &"prefix".into(), &prefix.into())?;
+    Reflect::set(&key, &"type".into(), &key_type.into())?;
+    Reflect::set(&key, &"blob".into(), &Uint8Array::from(blob.as_slice()))?;
+    Reflect::set(&key, &"comment".into(), &comment.into())?;
+
+    Ok(key)
+}
+
+/// [`parse`] parses one key line. Fields are separated by spaces or tabs, and double quotes
+/// in the prefix may hold either. The key type is the first field that looks like one and is
+/// followed by a blob of the same type, so a host such as `ssh-gw.example.com` stays in the
+/// prefix. If no field qualifies, the error is that of the first field that looked like a type.
+pub fn parse(line: &str) -> Result<PublicKey, SshKeyError> {
+    let prefix_start = skip_blanks(line, 0);
+    let mut prefix_end = prefix_start;
+    let mut type_start = prefix_start;
+    let mut first_err = None;
+
+    let (key_type, blob_start, blob_end, blob) = loop {
+        if type_start == line.len() {
+            return Err(first_err.unwrap_or(SshKeyError::MissingKeyType(prefix_start)));
+        }
+
+        let field_end = field_end(line, type_start);
+        let field = &line[type_start..field_end];
+        if is_key_type(field) {
+            match read_blob(line, type_start, field) {
+                Ok((blob_start, blob_end, blob)) => break (field, blob_start, blob_end, blob),
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+
+        prefix_end = field_end;
+        type_start = skip_blanks(line, field_end);
+    };
+
+    let mut fields = Fields(&blob);
+    fields.next();
+    let malformed = SshKeyError::Malformed(blob_start);
+
+    let key = match key_type {
+        "ssh-ed25519" => {
+            let key = fields.next().ok_or(malformed)?;
+            KeyData::Ed25519(key.try_into().map_err(|_| malformed)?)
+        }
+        "ssh-rsa" => KeyData::Rsa {
+            e: fields.next().ok_or(malformed)?.to_vec(),
+            n: fields.next().ok_or(malformed)?.to_vec(),
+        },
+        _ if key_type.starts_with("ecdsa-sha2-") => {
+            let curve = fields.next().ok_or(malformed)?;
+            KeyData::Ecdsa {
+                curve: String::from_utf8(curve.to_vec()).map_err(|_| malformed)?,
+                point: fields.next().ok_or(malformed)?.to_vec(),
+            }
+        }
+        _ => KeyData::Other,
+    };
+
+    if key != KeyData::Other && !fields.0.is_empty() {
+        return Err(malformed);
+    }
+
+    Ok(PublicKey {
+        prefix: line[prefix_start..prefix_end].to_string(),
+        key_type: key_type.to_string(),
+        key,
+        comment: line[blob_end..]
+            .trim_matches([' ', '\t', '\r', '\n'])
+            .to_string(),
+        blob,
+    })
+}
+
+/// [`read_blob`] decodes the blob following `key_type`, which starts at `type_start`, and
+/// checks that it opens with the same type. Returns where the blob starts and ends in `line`.
+fn read_blob(
+    line: &str,
+    type_start: usize,
+    key_type: &str,
+) -> Result<(usize, usize, Vec<u8>), SshKeyError> {
+    let blob_start = skip_blanks(line, type_start + key_type.len());
+    let blob_end = field_end(line, blob_start);
+    if blob_start == blob_end {
+        return Err(SshKeyError::MissingBlob(type_start));
+    }
+
+    let mut blob = Vec::new();
+    decode_to(&line.as_bytes()[blob_start..blob_end], &mut blob)
+        .map_err(|err| SshKeyError::InvalidBase64(blob_start, err.shift(blob_start)))?;
+
+    match Fields(&blob).next() {
+        None => Err(SshKeyError::Malformed(blob_start)),
+        Some(field) if field != key_type.as_bytes() => Err(SshKeyError::TypeMismatch(blob_start)),
+        Some(_) => Ok((blob_start, blob_end, blob)),
+    }
+}
+
+/// [`Fields`] reads the length-prefixed strings of a blob, stopping at the first one that is
+/// cut short.
+struct Fields<'a>(&'a [u8]);
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let (len, rest) = self.0.split_first_chunk::<4>()?;
+        let len = u32::from_be_bytes(*len) as usize;
+
+        if rest.len() < len {
+            return None;
+        }
+
+        let (field, rest) = rest.split_at(len);
+        self.0 = rest;
+        Some(field)
+    }
+}
+
+fn is_key_type(field: &str) -> bool {
+    ["ssh-", "ecdsa-sha2-", "sk-ssh-", "sk-ecdsa-sha2-"]
+        .iter()
+        .any(|prefix| field.starts_with(prefix))
+        && !field.contains(['"', '=', ','])
+}
+
+fn skip_blanks(line: &str, from: usize) -> usize {
+    line[from..]
+        .find(|c| c != ' ' && c != '\t')
+        .map_or(line.len(), |i| from + i)
+}
+
+/// [`field_end`] returns where the field starting at `from` ends, skipping over quoted blanks.
+fn field_end(line: &str, from: usize) -> usize {
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (i, c) in line[from..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ' ' | '\t' | '\r' | '\n' if !quoted => return from + i,
+            _ => {}
+        }
+    }
+
+    line.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::engine::general_purpose::STANDARD;
+    use crate::engine::Engine;
+
+    fn blob(fields: &[&[u8]]) -> Vec<u8> {
+        fields
+            .iter()
+            .flat_map(|field| {
+                (field.len() as u32)
+                    .to_be_bytes()
+                    .into_iter()
+                    .chain(field.iter().copied())
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_ed25519() -> Result<(), SshKeyError> {
+        let blob = blob(&[b"ssh-ed25519", &[7; 32]]);
+        let line = format!("ssh-ed25519 {} alice@example.com\n", STANDARD.encode(&blob));
+
+        assert_eq!(
+            parse(&line)?,
+            PublicKey {
+                prefix: String::new(),
+                key_type: "ssh-ed25519".to_string(),
+                key: KeyData::Ed25519([7; 32]),
+                comment: "alice@example.com".to_string(),
+                blob,
+            }
+        );
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rsa_and_ecdsa() -> Result<(), SshKeyError> {
+        let rsa = blob(&[b"ssh-rsa", &[1, 0, 1], &[0, 0xc3, 0x5a]]);
+        let line = format!("ssh-rsa {}", STANDARD.encode(&rsa));
+        assert_eq!(
+            parse(&line)?.key,
+            KeyData::Rsa {
+                e: vec![1, 0, 1],
+                n: vec![0, 0xc3, 0x5a],
+            }
+        );
+        assert_eq!(parse(&line)?.comment, "");
+
+        let ecdsa = blob(&[b"ecdsa-sha2-nistp256", b"nistp256", &[4; 65]]);
+        let line = format!("ecdsa-sha2-nistp256 {} two words", STANDARD.encode(&ecdsa));
+        let key = parse(&line)?;
+        assert_eq!(
+            key.key,
+            KeyData::Ecdsa {
+                curve: "nistp256".to_string(),
+                point: vec![4; 65],
+            }
+        );
+        assert_eq!(key.comment, "two words");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_prefixes() -> Result<(), SshKeyError> {
+        let encoded = STANDARD.encode(blob(&[b"ssh-ed25519", &[0; 32]]));
+
+        let line = format!("no-pty,command=\"echo a b\" ssh-ed25519 {encoded} ci");
+        let key = parse(&line)?;
+        assert_eq!(key.prefix, "no-pty,command=\"echo a b\"");
+        assert_eq!(key.comment, "ci");
+
+        let line = format!("@cert-authority *.example.com\tssh-ed25519 {encoded}");
+        assert_eq!(parse(&line)?.prefix, "@cert-authority *.example.com");
+
+        let line = format!("ssh-gw.example.com,ssh-rsa ssh-ed25519 {encoded} host");
+        let key = parse(&line)?;
+        assert_eq!(key.prefix, "ssh-gw.example.com,ssh-rsa");
+        assert_eq!(key.key_type, "ssh-ed25519");
+        assert_eq!(key.comment, "host");
+
+        let line = format!("ssh-gw.example.com ssh-ed25519 {encoded}");
+        assert_eq!(parse(&line)?.prefix, "ssh-gw.example.com");
+
+        let cert = STANDARD.encode(blob(&[b"ssh-ed25519-cert-v01@openssh.com", b"nonce"]));
+        let line = format!("ssh-ed25519-cert-v01@openssh.com {cert}");
+        assert_eq!(parse(&line)?.key, KeyData::Other);
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_errors() {
+        let ed25519 = STANDARD.encode(blob(&[b"ssh-ed25519", &[0; 32]]));
+
+        assert_eq!(parse("  "), Err(SshKeyError::MissingKeyType(2)));
+        assert_eq!(
+            parse("from=\"x\" AAAA"),
+            Err(SshKeyError::MissingKeyType(0))
+        );
+        assert_eq!(parse("  ssh-rsa  "), Err(SshKeyError::MissingBlob(2)));
+        assert_eq!(
+            parse("ssh-rsa AA!A"),
+            Err(SshKeyError::InvalidBase64(
+                8,
+                DecodeError::InvalidByte(10, b'!')
+            ))
+        );
+        assert_eq!(
+            parse(&format!("ssh-rsa  {ed25519}")),
+            Err(SshKeyError::TypeMismatch(9))
+        );
+
+        let short = STANDARD.encode(blob(&[b"ssh-ed25519", &[0; 31]]));
+        assert_eq!(
+            parse(&format!("ssh-ed25519 {short}")),
+            Err(SshKeyError::Malformed(12))
+        );
+
+        let truncated = STANDARD.encode(&blob(&[b"ssh-rsa", &[1, 0, 1]])[..12]);
+        assert_eq!(
+            parse(&format!("ssh-rsa {truncated}")),
+            Err(SshKeyError::Malformed(8))
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Decode base64 straight into numeric arrays, and encode them back, in a chosen byte order.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use paste::paste;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+
+#[cfg(feature = "std")]
+use crate::buffer_source::byte_view;
+use crate::{decode_into, decoded_len, encode_to_string, DecodeError};
+
+/// [`ByteOrder`] is the order of the bytes within each element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl ByteOrder {
+    fn from_little_endian(little_endian: bool) -> Self {
+        match little_endian {
+            true => Self::Little,
+            false => Self::Big,
+        }
+    }
+}
+
+/// [`TypedError`] describes why ascii could not be decoded into elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypedError {
+    /// The ascii is not valid base64.
+    InvalidBase64(DecodeError),
+    /// The decoded length (first) is not a multiple of the element size (second).
+    InvalidElementLength(usize, usize),
+}
+
+impl From<DecodeError> for TypedError {
+    fn from(err: DecodeError) -> Self {
+        Self::InvalidBase64(err)
+    }
+}
+
+impl fmt::Display for TypedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBase64(err) => write!(f, "{err}"),
+            Self::InvalidElementLength(len, size) => {
+                write!(f, "decoded length {len} is not a multiple of {size}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TypedError {}
+
+impl From<TypedError> for JsValue {
+    fn from(err: TypedError) -> Self {
+        Self::from_str(&alloc::string::ToString::to_string(&err))
+    }
+}
+
+macro_rules! impl_typed {
+    ($ty:ident, $js_array:literal) => {
+        paste! {
+            #[doc = "[`decode_" $ty "`] decodes `ascii` into `" $ty "`s stored in `order`. The"]
+            #[doc = "decoded length must be a multiple of the element size."]
+            pub fn [<decode_ $ty>](ascii: &[u8], order: ByteOrder) -> Result<Vec<$ty>, TypedError> {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                let symbols = match ascii {
+                    [p @ .., b'=', b'='] | [p @ .., b'='] | p => p,
+                };
+                let len = decoded_len(symbols.len());
+                if len % SIZE != 0 {
+                    return Err(TypedError::InvalidElementLength(len, SIZE));
+                }
+                let mut elements = vec![<$ty>::default(); len / SIZE];
+
+                // SAFETY: the bytes cover exactly `elements`, and any bytes make a valid element.
+                let bytes = unsafe {
+                    core::slice::from_raw_parts_mut(elements.as_mut_ptr().cast::<u8>(), len)
+                };
+                decode_into(ascii, bytes)?;
+
+                for element in &mut elements {
+                    let bytes = element.to_ne_bytes();
+                    *element = match order {
+                        ByteOrder::Little => <$ty>::from_le_bytes(bytes),
+                        ByteOrder::Big => <$ty>::from_be_bytes(bytes),
+                    };
+                }
+
+                Ok(elements)
+            }
+
+            #[doc = "[`encode_" $ty "`] encodes `data` with padding, each element stored in `order`."]
+            pub fn [<encode_ $ty>](data: &[$ty], order: ByteOrder) -> String {
+                let bytes: Vec<u8> = data
+                    .iter()
+                    .flat_map(|element| match order {
+                        ByteOrder::Little => element.to_le_bytes(),
+                        ByteOrder::Big => element.to_be_bytes(),
+                    })
+                    .collect();
+
+                let mut ascii = String::new();
+                encode_to_string(&bytes, &mut ascii);
+                ascii
+            }
+
+            #[doc = "[`decode_" $ty "_js`] decodes ascii into a `" $js_array "`."]
+            #[wasm_bindgen(js_name = [<decode_ $ty>])]
+            pub fn [<decode_ $ty _js>](ascii: &[u8], little_endian: bool) -> Result<Vec<$ty>, JsValue> {
+                Ok([<decode_ $ty>](ascii, ByteOrder::from_little_endian(little_endian))?)
+            }
+
+            #[doc = "[`encode_" $ty "_js`] encodes a `" $js_array "` into a base64 string."]
+            #[cfg(not(feature = "std"))]
+            #[wasm_bindgen(js_name = [<encode_ $ty>])]
+            pub fn [<encode_ $ty _js>](data: &[$ty], little_endian: bool) -> String {
+                [<encode_ $ty>](data, ByteOrder::from_little_endian(little_endian))
+            }
+
+            #[doc = "[`encode_" $ty "_js`] encodes a `" $js_array "`, or the bytes of any other"]
+            #[doc = "`BufferSource` read as `" $ty "`s, into a base64 string."]
+            #[cfg(feature = "std")]
+            #[wasm_bindgen(js_name = [<encode_ $ty>])]
+            pub fn [<encode_ $ty _js>](data: &JsValue, little_endian: bool) -> Result<String, JsValue> {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                let mut bytes = byte_view(data)?.to_vec();
+                if bytes.len() % SIZE != 0 {
+                    return Err(js_sys::RangeError::new(concat!(
+                        "byte length must be a multiple of ",
+                        $js_array,
+                        ".BYTES_PER_ELEMENT"
+                    ))
+                    .into());
+                }
+
+                let order = ByteOrder::from_little_endian(little_endian);
+                for element in bytes.chunks_exact_mut(SIZE) {
+                    let value = <$ty>::from_ne_bytes(element.try_into().expect("Slice with incorrect length"));
+                    element.copy_from_slice(&match order {
+                        ByteOrder::Little => value.to_le_bytes(),
+                        ByteOrder::Big => value.to_be_bytes(),
+                    });
+                }
+
+                let mut ascii = String::new();
+                encode_to_string(&bytes, &mut ascii);
+                Ok(ascii)
+            }
+        }
+    };
+}
+
+impl_typed!(i16, "Int16Array");
+impl_typed!(u32, "Uint32Array");
+impl_typed!(i64, "BigInt64Array");
+impl_typed!(f32, "Float32Array");
+impl_typed!(f64, "Float64Array");
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_round_trip() -> Result<(), TypedError> {
+        let samples = [0.0f32, -1.5, 3.25, f32::MAX, f32::MIN_POSITIVE];
+
+        for order in [ByteOrder::Little, ByteOrder::Big] {
+            assert_eq!(
+                decode_f32(encode_f32(&samples, order).as_bytes(), order)?,
+                samples
+            );
+            assert_eq!(
+                decode_i16(encode_i16(&[-2, 7], order).as_bytes(), order)?,
+                [-2, 7]
+            );
+            assert_eq!(
+                decode_u32(encode_u32(&[1, u32::MAX], order).as_bytes(), order)?,
+                [1, u32::MAX]
+            );
+            assert_eq!(
+                decode_i64(encode_i64(&[i64::MIN], order).as_bytes(), order)?,
+                [i64::MIN]
+            );
+            assert_eq!(
+                decode_f64(encode_f64(&[1e-300], order).as_bytes(), order)?,
+                [1e-300]
+            );
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_byte_order() -> Result<(), TypedError> {
+        // 0x00 0x01 0x02 0x03
+        assert_eq!(decode_u32(b"AAECAw==", ByteOrder::Little)?, [0x0302_0100]);
+        assert_eq!(decode_u32(b"AAECAw==", ByteOrder::Big)?, [0x0001_0203]);
+        assert_eq!(encode_i16(&[1], ByteOrder::Big), "AAE=");
+        assert_eq!(encode_i16(&[1], ByteOrder::Little), "AQA=");
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rejects_partial_element() {
+        assert_eq!(
+            decode_f32(b"AAEC", ByteOrder::Little),
+            Err(TypedError::InvalidElementLength(3, 4))
+        );
+        assert_eq!(
+            decode_f32(b"AA!CAA==", ByteOrder::Little),
+            Err(TypedError::InvalidBase64(DecodeError::InvalidByte(2, b'!')))
+        );
+        assert_eq!(decode_i16(b"", ByteOrder::Little), Ok(Vec::new()));
+    }
+
+    #[cfg(feature = "std")]
+    #[wasm_bindgen_test]
+    fn test_js_rejects_partial_element() {
+        let data = js_sys::Uint8Array::from(&[0u8; 6][..]);
+        let err = js_sys::RangeError::from(encode_f32_js(&data, true).unwrap_err());
+
+        assert_eq!(
+            String::from(err.message()),
+            "byte length must be a multiple of Float32Array.BYTES_PER_ELEMENT"
+        );
+    }
+}